@@ -0,0 +1,134 @@
+use crate::config::LIN_CONFIG_LOC;
+use std::collections::{BTreeMap, BTreeSet};
+use tokio::fs;
+
+pub const ANNOTATIONS_FILE: &str = "annotations";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModNote {
+    pub tags: Vec<String>,
+    pub note: String,
+}
+
+impl ModNote {
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.note.is_empty()
+    }
+}
+
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split(',').map(|tag| tag.trim()) {
+        if !tag.is_empty() && !tags.iter().any(|known| known == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+// Tags and notes are keyed on workshop ID and kept apart from presets,
+// so they follow a mod through every preset and profile
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Annotations {
+    pub notes: BTreeMap<String, ModNote>,
+}
+
+impl Annotations {
+    pub fn get(&self, workshop_id: &str) -> ModNote {
+        self.notes.get(workshop_id).cloned().unwrap_or_default()
+    }
+
+    pub fn set_tags(&mut self, workshop_id: &str, input: &str) {
+        self.notes.entry(workshop_id.to_string()).or_default().tags = parse_tags(input);
+        self.prune(workshop_id);
+    }
+
+    pub fn set_note(&mut self, workshop_id: &str, note: String) {
+        self.notes.entry(workshop_id.to_string()).or_default().note = note;
+        self.prune(workshop_id);
+    }
+
+    fn prune(&mut self, workshop_id: &str) {
+        if self.notes.get(workshop_id).is_some_and(|note| note.is_empty()) {
+            self.notes.remove(workshop_id);
+        }
+    }
+
+    pub fn has_tag(&self, workshop_id: &str, tag: &str) -> bool {
+        self.notes
+            .get(workshop_id)
+            .is_some_and(|note| note.tags.iter().any(|known| known == tag))
+    }
+
+    pub fn all_tags(&self) -> Vec<String> {
+        let tags: BTreeSet<&String> = self.notes.values().flat_map(|note| &note.tags).collect();
+        tags.into_iter().cloned().collect()
+    }
+
+    //=== Stored as ini style sections, newlines in notes are escaped =====
+
+    pub fn parse(content: &str) -> Annotations {
+        let mut annotations = Annotations::default();
+        let mut current: Option<String> = None;
+
+        for line in content.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') && trimmed.ends_with(']') {
+                current = Some(trimmed[1..trimmed.len() - 1].to_string());
+            } else if let Some((key, value)) = trimmed.split_once('=')
+                && let Some(workshop_id) = &current
+            {
+                let entry = annotations.notes.entry(workshop_id.clone()).or_default();
+                match key.trim() {
+                    "tags" => entry.tags = parse_tags(value),
+                    "note" => entry.note = value.trim().replace("\\n", "\n"),
+                    _ => {}
+                }
+            }
+        }
+        annotations
+    }
+
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        for (workshop_id, note) in &self.notes {
+            output.push_str(&format!("[{}]\n", workshop_id));
+            output.push_str(&format!("tags={}\n", note.tags.join(", ")));
+            output.push_str(&format!("note={}\n\n", note.note.replace('\n', "\\n")));
+        }
+        output
+    }
+}
+
+pub async fn load_annotations() -> Annotations {
+    match fs::read_to_string(LIN_CONFIG_LOC.to_owned() + ANNOTATIONS_FILE).await {
+        Ok(content) => Annotations::parse(&content),
+        Err(_err) => Annotations::default(),
+    }
+}
+
+pub async fn save_annotations(annotations: Annotations) -> String {
+    match fs::write(LIN_CONFIG_LOC.to_owned() + ANNOTATIONS_FILE, annotations.render()).await {
+        Ok(_) => format!("Saved notes for {} mods", annotations.notes.len()),
+        Err(err) => format!("Error writing notes -> Err: {}", err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn annotations_round_trip_and_collect_tags() {
+        let mut annotations = Annotations::default();
+        annotations.set_tags("2169435993", "QoL, required by event, QoL");
+        annotations.set_note("2169435993", "Needs the patch\nfrom discord".to_string());
+        annotations.set_tags("2850935956", "breaks with X");
+        annotations.set_tags("111", "");
+
+        assert_eq!(Annotations::parse(&annotations.render()), annotations);
+        assert_eq!(annotations.all_tags(), vec!["QoL", "breaks with X", "required by event"]);
+        assert!(annotations.has_tag("2169435993", "QoL"));
+        assert!(!annotations.notes.contains_key("111"));
+    }
+}
@@ -0,0 +1,478 @@
+use crate::config::LIN_CONFIG_LOC;
+use crate::custom_theme::{button, label_container_style, label_font, scrollable, text};
+use crate::localmodinfo::{collect_files, file_hash};
+use crate::text_encoding::decode_text;
+use crate::{AppMessage, State};
+use iced::widget::{column, container, row};
+use iced::Length;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tokio::fs;
+
+pub const FINGERPRINTS_FILE: &str = "fingerprints";
+// Listing every file of a reworked mod buries the rest of the report
+const CHANGED_FILE_LIMIT: usize = 20;
+
+// Steam rewrites every file of an item it updates, so with content hashes a
+// file whose size didn't change is only modified when its hash did. Without
+// them (hash 0) a new mtime is all there is to go on
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FileStamp {
+    pub size: u64,
+    pub modified: u64,
+    pub hash: u64,
+}
+
+impl FileStamp {
+    fn changed(&self, other: &FileStamp) -> bool {
+        match self.hash != 0 && other.hash != 0 {
+            true => self.size != other.size || self.hash != other.hash,
+            false => self.size != other.size || self.modified != other.modified,
+        }
+    }
+}
+
+// File stamps plus the mod.info fields of each mod in the item
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ItemFingerprint {
+    pub files: BTreeMap<String, FileStamp>,
+    pub info: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+pub type Fingerprints = BTreeMap<String, ItemFingerprint>;
+// Fingerprints per workshop root, switching roots doesn't replace the other's history
+pub type FingerprintStore = BTreeMap<String, Fingerprints>;
+
+// root=<workshop folder> blocks of [workshop ID] sections with
+// file=path\tsize\tmtime\thash and info=mod\tkey\tvalue lines
+pub fn render_fingerprints(store: &FingerprintStore) -> String {
+    let mut output = String::new();
+    for (root, fingerprints) in store {
+        output.push_str(&format!("root={}\n", root));
+        for (workshop_id, item) in fingerprints {
+            output.push_str(&format!("[{}]\n", workshop_id));
+            for (file, stamp) in &item.files {
+                output.push_str(&format!(
+                    "file={}\t{}\t{}\t{:016x}\n",
+                    file, stamp.size, stamp.modified, stamp.hash
+                ));
+            }
+            for (mod_folder, fields) in &item.info {
+                for (key, value) in fields {
+                    output.push_str(&format!("info={}\t{}\t{}\n", mod_folder, key, value));
+                }
+            }
+        }
+    }
+    output
+}
+
+pub fn parse_fingerprints(content: &str) -> FingerprintStore {
+    let mut store = FingerprintStore::new();
+    let mut root: Option<String> = None;
+    let mut current: Option<String> = None;
+    for line in content.lines() {
+        if let Some(path) = line.strip_prefix("root=") {
+            store.entry(path.to_string()).or_default();
+            root = Some(path.to_string());
+            current = None;
+            continue;
+        }
+        let Some(fingerprints) = root.as_ref().and_then(|root| store.get_mut(root)) else {
+            continue;
+        };
+        if let Some(workshop_id) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            fingerprints.entry(workshop_id.to_string()).or_default();
+            current = Some(workshop_id.to_string());
+            continue;
+        }
+        let Some(item) = current.as_ref().and_then(|id| fingerprints.get_mut(id)) else {
+            continue;
+        };
+        if let Some(file) = line.strip_prefix("file=") {
+            let parts: Vec<&str> = file.split('\t').collect();
+            if let [path, size, modified, hash] = parts.as_slice() {
+                item.files.insert(
+                    path.to_string(),
+                    FileStamp {
+                        size: size.parse().unwrap_or_default(),
+                        modified: modified.parse().unwrap_or_default(),
+                        hash: u64::from_str_radix(hash, 16).unwrap_or_default(),
+                    },
+                );
+            }
+        } else if let Some(info) = line.strip_prefix("info=") {
+            let parts: Vec<&str> = info.splitn(3, '\t').collect();
+            if let [mod_folder, key, value] = parts.as_slice() {
+                item.info
+                    .entry(mod_folder.to_string())
+                    .or_default()
+                    .insert(key.to_string(), value.to_string());
+            }
+        }
+    }
+    store
+}
+
+fn parse_info_fields(content: &str) -> BTreeMap<String, String> {
+    content
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .filter(|(key, _value)| !key.is_empty())
+        .collect()
+}
+
+// Content hashes are opt in, reading a whole workshop folder after every Steam
+// update is slow. Files whose size and mtime match the previous scan keep their
+// hash, only the rest are read on the blocking pool
+pub async fn fingerprint_item(
+    item_dir: &Path,
+    previous: Option<&ItemFingerprint>,
+    content_hashes: bool,
+) -> ItemFingerprint {
+    let mut fingerprint = ItemFingerprint::default();
+    let mut files: Vec<PathBuf> = Vec::new();
+    let mut unhashed: Vec<(String, PathBuf)> = Vec::new();
+    let _ = collect_files(item_dir, &mut files).await;
+
+    for file in files {
+        let Ok(metadata) = fs::metadata(&file).await else {
+            continue;
+        };
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        let relative = file.strip_prefix(item_dir).unwrap_or(&file);
+        let key = relative.to_string_lossy().to_string();
+        let known = previous
+            .and_then(|previous| previous.files.get(&key))
+            .filter(|stamp| stamp.size == metadata.len() && stamp.modified == modified && stamp.hash != 0);
+        let hash = match known {
+            Some(stamp) if content_hashes => stamp.hash,
+            _ => {
+                if content_hashes {
+                    unhashed.push((key.clone(), file.clone()));
+                }
+                0
+            }
+        };
+        fingerprint.files.insert(
+            key,
+            FileStamp {
+                size: metadata.len(),
+                modified,
+                hash,
+            },
+        );
+
+        // mods/<X>/mod.info
+        let parts: Vec<String> = relative
+            .components()
+            .map(|part| part.as_os_str().to_string_lossy().to_string())
+            .collect();
+        if let [mods, mod_folder, info] = parts.as_slice()
+            && mods == "mods"
+            && info == "mod.info"
+            && let Ok(bytes) = fs::read(&file).await
+        {
+            fingerprint
+                .info
+                .insert(mod_folder.clone(), parse_info_fields(&decode_text(&bytes).0));
+        }
+    }
+
+    let hashes = tokio::task::spawn_blocking(move || {
+        unhashed
+            .into_iter()
+            .map(|(key, file)| (key, file_hash(&file).unwrap_or_default()))
+            .collect::<Vec<(String, u64)>>()
+    })
+    .await
+    .unwrap_or_default();
+    for (key, hash) in hashes {
+        if let Some(stamp) = fingerprint.files.get_mut(&key) {
+            stamp.hash = hash;
+        }
+    }
+    fingerprint
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ItemChange {
+    pub workshop_id: String,
+    pub added_files: Vec<String>,
+    pub removed_files: Vec<String>,
+    pub modified_files: Vec<String>,
+    pub info_changes: Vec<String>,
+}
+
+impl ItemChange {
+    fn is_empty(&self) -> bool {
+        self.added_files.is_empty()
+            && self.removed_files.is_empty()
+            && self.modified_files.is_empty()
+            && self.info_changes.is_empty()
+    }
+}
+
+fn list_entries(value: Option<&String>) -> BTreeSet<String> {
+    value
+        .map(|value| {
+            value
+                .split([',', ';'])
+                .map(|entry| entry.trim().trim_start_matches('\\').to_string())
+                .filter(|entry| !entry.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn info_changes(
+    old: &BTreeMap<String, BTreeMap<String, String>>,
+    new: &BTreeMap<String, BTreeMap<String, String>>,
+) -> Vec<String> {
+    let mut changes: Vec<String> = Vec::new();
+    let mod_folders: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    for mod_folder in mod_folders {
+        let (Some(old_fields), Some(new_fields)) = (old.get(mod_folder), new.get(mod_folder)) else {
+            changes.push(match new.contains_key(mod_folder) {
+                true => format!("{}: new mod", mod_folder),
+                false => format!("{}: mod removed", mod_folder),
+            });
+            continue;
+        };
+        let keys: BTreeSet<&String> = old_fields.keys().chain(new_fields.keys()).collect();
+        for key in keys {
+            let (old_value, new_value) = (old_fields.get(key), new_fields.get(key));
+            if old_value == new_value {
+                continue;
+            }
+            // Dependencies are what usually breaks a server, name them one by one
+            if key == "require" {
+                let (before, after) = (list_entries(old_value), list_entries(new_value));
+                for added in after.difference(&before) {
+                    changes.push(format!("{}: new require {}", mod_folder, added));
+                }
+                for removed in before.difference(&after) {
+                    changes.push(format!("{}: no longer requires {}", mod_folder, removed));
+                }
+                continue;
+            }
+            changes.push(format!(
+                "{}: {}= {} -> {}",
+                mod_folder,
+                key,
+                old_value.map(|value| value.as_str()).unwrap_or("(none)"),
+                new_value.map(|value| value.as_str()).unwrap_or("(none)")
+            ));
+        }
+    }
+    changes
+}
+
+pub fn compare_items(workshop_id: &str, old: &ItemFingerprint, new: &ItemFingerprint) -> ItemChange {
+    let mut change = ItemChange {
+        workshop_id: workshop_id.to_string(),
+        info_changes: info_changes(&old.info, &new.info),
+        ..Default::default()
+    };
+    for (file, stamp) in &new.files {
+        match old.files.get(file) {
+            None => change.added_files.push(file.clone()),
+            Some(old_stamp) if old_stamp.changed(stamp) => change.modified_files.push(file.clone()),
+            Some(_) => {}
+        }
+    }
+    change.removed_files = old
+        .files
+        .keys()
+        .filter(|file| !new.files.contains_key(*file))
+        .cloned()
+        .collect();
+    change
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ChangeReport {
+    pub first_scan: bool,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub updated: Vec<ItemChange>,
+    pub status: String,
+}
+
+impl ChangeReport {
+    pub fn count(&self) -> usize {
+        self.added.len() + self.removed.len() + self.updated.len()
+    }
+
+    pub fn compare(old: &Fingerprints, new: &Fingerprints) -> ChangeReport {
+        ChangeReport {
+            first_scan: old.is_empty(),
+            added: new.keys().filter(|id| !old.contains_key(*id)).cloned().collect(),
+            removed: old.keys().filter(|id| !new.contains_key(*id)).cloned().collect(),
+            updated: new
+                .iter()
+                .filter_map(|(id, item)| old.get(id).map(|old_item| compare_items(id, old_item, item)))
+                .filter(|change| !change.is_empty())
+                .collect(),
+            status: String::new(),
+        }
+    }
+
+    pub fn changes_view<'a>(&'a self, names: &HashMap<String, String>) -> iced::widget::Container<'a, AppMessage> {
+        let label = |workshop_id: &String| {
+            format!(
+                "{} ({})",
+                names.get(workshop_id).map(|name| name.as_str()).unwrap_or("Unknown"),
+                workshop_id
+            )
+        };
+
+        let mut item_col = column![text(format!(
+            "New items: {}, removed items: {}",
+            self.added.len(),
+            self.removed.len()
+        ))];
+        for workshop_id in &self.added {
+            item_col = item_col.push(text(format!("+ {}", label(workshop_id))));
+        }
+        for workshop_id in &self.removed {
+            item_col = item_col.push(text(format!("- {}", workshop_id)));
+        }
+
+        let mut updated_col = column![text(format!("Updated items: {}", self.updated.len()))].spacing(6);
+        for change in &self.updated {
+            let mut change_col = column![text(label(&change.workshop_id)).font(label_font())];
+            for info_change in &change.info_changes {
+                change_col = change_col.push(text(format!("  {}", info_change)).style(iced::widget::text::primary));
+            }
+            for (marker, files) in [
+                ("+", &change.added_files),
+                ("-", &change.removed_files),
+                ("~", &change.modified_files),
+            ] {
+                for file in files.iter().take(CHANGED_FILE_LIMIT) {
+                    change_col = change_col.push(text(format!("  {} {}", marker, file)));
+                }
+                if files.len() > CHANGED_FILE_LIMIT {
+                    change_col = change_col.push(text(format!(
+                        "  {} {} more files",
+                        marker,
+                        files.len() - CHANGED_FILE_LIMIT
+                    )));
+                }
+            }
+            updated_col = updated_col.push(change_col);
+        }
+
+        let summary = match self.first_scan {
+            true => String::from("First scan, the next scan will be compared against this one"),
+            false => format!("{} workshop items changed since the last scan", self.count()),
+        };
+
+        container(column![
+            row![
+                container(text("Changed Since Last Scan").font(label_font()))
+                    .padding(8)
+                    .style(label_container_style)
+            ],
+            text(summary),
+            row![
+                scrollable(updated_col).width(Length::FillPortion(2)),
+                scrollable(item_col).width(Length::FillPortion(1))
+            ]
+            .height(Length::Fill)
+            .padding(5),
+            row![
+                button(text("Back to Mods")).on_press(AppMessage::UpdateView(State::LoadedMain)),
+                text(&self.status)
+            ]
+            .spacing(10)
+        ])
+    }
+}
+
+// Compares against the previous scan of the same workshop root, then stores this one
+pub async fn detect_changes(
+    workshop_location: String,
+    workshop_ids: Vec<String>,
+    content_hashes: bool,
+) -> ChangeReport {
+    let mut store = match fs::read_to_string(LIN_CONFIG_LOC.to_owned() + FINGERPRINTS_FILE).await {
+        Ok(content) => parse_fingerprints(&content),
+        Err(_err) => FingerprintStore::new(),
+    };
+    let previous = store.remove(&workshop_location).unwrap_or_default();
+    let mut current = Fingerprints::new();
+    for workshop_id in workshop_ids {
+        let item_dir = Path::new(&workshop_location).join(&workshop_id);
+        let item = fingerprint_item(&item_dir, previous.get(&workshop_id), content_hashes).await;
+        current.insert(workshop_id, item);
+    }
+
+    let mut report = ChangeReport::compare(&previous, &current);
+    store.insert(workshop_location, current);
+    if let Err(err) = fs::write(LIN_CONFIG_LOC.to_owned() + FINGERPRINTS_FILE, render_fingerprints(&store)).await {
+        report.status = format!("Error writing fingerprints -> Err: {}", err);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_file_and_require_changes() {
+        let stamp = |size: u64, modified: u64, hash: u64| FileStamp { size, modified, hash };
+        let old_item = ItemFingerprint {
+            files: BTreeMap::from([
+                ("mods/A/mod.info".to_string(), stamp(40, 100, 1)),
+                ("mods/A/media/old.lua".to_string(), stamp(10, 100, 2)),
+                ("mods/A/media/same.lua".to_string(), stamp(7, 100, 3)),
+            ]),
+            info: BTreeMap::from([(
+                "A".to_string(),
+                BTreeMap::from([
+                    ("name".to_string(), "A".to_string()),
+                    ("require".to_string(), "Base".to_string()),
+                ]),
+            )]),
+        };
+        let mut new_item = old_item.clone();
+        new_item.files.remove("mods/A/media/old.lua");
+        new_item.files.insert("mods/A/mod.info".to_string(), stamp(52, 200, 4));
+        new_item.files.insert("mods/A/media/new.lua".to_string(), stamp(5, 200, 5));
+        // Rewritten by the update with the same content
+        new_item.files.insert("mods/A/media/same.lua".to_string(), stamp(7, 200, 3));
+        new_item
+            .info
+            .get_mut("A")
+            .unwrap()
+            .insert("require".to_string(), "Base,\\tsarslib".to_string());
+
+        let old = Fingerprints::from([("1".to_string(), old_item), ("2".to_string(), ItemFingerprint::default())]);
+        let new = Fingerprints::from([("1".to_string(), new_item), ("3".to_string(), ItemFingerprint::default())]);
+        let store = FingerprintStore::from([("/steam/content/108600".to_string(), old.clone())]);
+        assert_eq!(parse_fingerprints(&render_fingerprints(&store)), store);
+
+        let report = ChangeReport::compare(&old, &new);
+        assert_eq!(report.added, vec!["3"]);
+        assert_eq!(report.removed, vec!["2"]);
+        assert_eq!(report.updated.len(), 1);
+        assert_eq!(report.updated[0].added_files, vec!["mods/A/media/new.lua"]);
+        assert_eq!(report.updated[0].removed_files, vec!["mods/A/media/old.lua"]);
+        assert_eq!(report.updated[0].modified_files, vec!["mods/A/mod.info"]);
+        assert_eq!(report.updated[0].info_changes, vec!["A: new require tsarslib"]);
+
+        // Without content hashes the rewrite shows up through its new mtime
+        assert!(stamp(7, 100, 0).changed(&stamp(7, 200, 0)));
+        assert!(!stamp(7, 100, 0).changed(&stamp(7, 100, 0)));
+    }
+}
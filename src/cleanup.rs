@@ -0,0 +1,227 @@
+use crate::config::{LIN_CONFIG_LOC, is_preset, read_config};
+use crate::custom_theme::{button, checkbox, label_container_style, label_font, scrollable, text};
+use crate::disk_usage::DiskUsage;
+use crate::localmodinfo::{format_size, path_collect};
+use crate::{AppMessage, State};
+use iced::widget::{column, container, row};
+use iced::{Length, alignment::Vertical};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+pub const TRASH_DIR: &str = "zsmm_trash";
+const UNSUBSCRIBE_URL: &str = "https://steamcommunity.com/sharedfiles/filedetails/?id=";
+
+// Kept next to the workshop folder so moving is a rename on the same drive,
+// and outside it so the game and the scan no longer see the items
+pub fn trash_dir(workshop_location: &str) -> PathBuf {
+    let workshop = Path::new(workshop_location);
+    workshop.parent().unwrap_or(workshop).join(TRASH_DIR)
+}
+
+// Workshop IDs that are selected in a saved preset, a profile's preset or the current selection
+pub fn referenced_ids(
+    presets: &[HashMap<String, bool>],
+    names_and_details: &HashMap<String, [String; 3]>,
+) -> HashSet<String> {
+    presets
+        .iter()
+        .flat_map(|selections| selections.iter())
+        .filter(|(_name, selected)| **selected)
+        .filter_map(|(name, _selected)| names_and_details.get(name))
+        .map(|details| details[0].clone())
+        .collect()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CleanupScan {
+    // (workshop ID, mod name) of items nothing references
+    pub unused: Vec<(String, String)>,
+    pub trashed: Vec<String>,
+    pub presets_read: usize,
+}
+
+pub async fn scan_unused(
+    workshop_location: String,
+    workshop_ids: Vec<String>,
+    names_and_details: HashMap<String, [String; 3]>,
+    current: HashMap<String, bool>,
+    profile_presets: Vec<String>,
+) -> CleanupScan {
+    let mut preset_paths: Vec<String> = path_collect(LIN_CONFIG_LOC)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|path| is_preset(path) && Path::new(path).is_file())
+        .collect();
+    for preset in profile_presets {
+        let path = LIN_CONFIG_LOC.to_owned() + &preset;
+        if !preset.is_empty() && !preset_paths.contains(&path) && Path::new(&path).is_file() {
+            preset_paths.push(path);
+        }
+    }
+
+    let mut presets: Vec<HashMap<String, bool>> = vec![current];
+    for path in &preset_paths {
+        presets.push(read_config(path.clone()).await.1);
+    }
+    let referenced = referenced_ids(&presets, &names_and_details);
+    let names: HashMap<&String, &String> = names_and_details
+        .iter()
+        .map(|(name, details)| (&details[0], name))
+        .collect();
+
+    let mut unused: Vec<(String, String)> = workshop_ids
+        .into_iter()
+        .filter(|id| !referenced.contains(id))
+        .map(|id| {
+            let name = names.get(&id).map(|name| name.to_string()).unwrap_or_default();
+            (id, name)
+        })
+        .collect();
+    unused.sort();
+
+    let mut trashed: Vec<String> = path_collect(&trash_dir(&workshop_location).to_string_lossy())
+        .await
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|path| Path::new(path).file_name().map(|name| name.to_string_lossy().to_string()))
+        .collect();
+    trashed.sort();
+
+    CleanupScan {
+        unused,
+        trashed,
+        presets_read: preset_paths.len(),
+    }
+}
+
+pub async fn move_to_trash(workshop_location: String, workshop_ids: Vec<String>) -> (Vec<String>, String) {
+    let trash = trash_dir(&workshop_location);
+    if let Err(err) = tokio::fs::create_dir_all(&trash).await {
+        return (Vec::new(), format!("Error creating {} -> Err: {}", trash.display(), err));
+    }
+    let mut moved: Vec<String> = Vec::new();
+    let mut errors: Vec<String> = Vec::new();
+    for id in workshop_ids {
+        match tokio::fs::rename(Path::new(&workshop_location).join(&id), trash.join(&id)).await {
+            Ok(_) => moved.push(id),
+            Err(err) => errors.push(format!("{} -> {}", id, err)),
+        }
+    }
+    let status = format!("Moved {} items to {}. {}", moved.len(), trash.display(), errors.join(", "));
+    (moved, status)
+}
+
+pub async fn restore_from_trash(workshop_location: String, workshop_id: String) -> String {
+    let source = trash_dir(&workshop_location).join(&workshop_id);
+    match tokio::fs::rename(&source, Path::new(&workshop_location).join(&workshop_id)).await {
+        Ok(_) => format!("Restored {}, rescan the mod folder to list it again", workshop_id),
+        Err(err) => format!("Error restoring {} -> Err: {}", workshop_id, err),
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Cleanup {
+    pub scan: CleanupScan,
+    pub marked: HashSet<String>,
+    pub status: String,
+}
+
+impl Cleanup {
+    pub fn marked_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.marked.iter().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    // Steam only stops re-downloading an item once it's unsubscribed
+    pub fn unsubscribe_list(&self) -> String {
+        self.marked_ids()
+            .iter()
+            .map(|id| format!("{}{}", UNSUBSCRIBE_URL, id))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    pub fn cleanup_view<'a>(&'a self, disk_usage: &'a DiskUsage) -> iced::widget::Container<'a, AppMessage> {
+        let marked_size: u64 = disk_usage.total(&self.marked_ids());
+        let unused_size: u64 =
+            disk_usage.total(&self.scan.unused.iter().map(|(id, _name)| id.clone()).collect::<Vec<String>>());
+
+        let mut unused_col = column![text(format!(
+            "Not used by the current selection or any of {} presets: {} items, {}",
+            self.scan.presets_read,
+            self.scan.unused.len(),
+            format_size(unused_size)
+        ))];
+        for (id, name) in &self.scan.unused {
+            let id_clone = id.clone();
+            unused_col = unused_col.push(
+                row![
+                    checkbox(format!("{} ({})", name, id), self.marked.contains(id))
+                        .on_toggle(move |bool| AppMessage::CleanupToggle(id_clone.clone(), bool)),
+                    text(disk_usage.size_label(id)).size(12)
+                ]
+                .spacing(10)
+                .align_y(Vertical::Center),
+            );
+        }
+
+        let mut trash_col = column![text(format!("In the trash folder: {}", self.scan.trashed.len()))];
+        for id in &self.scan.trashed {
+            trash_col = trash_col.push(
+                row![text(id), button(text("Restore")).on_press(AppMessage::RestoreItem(id.clone()))]
+                    .spacing(10)
+                    .align_y(Vertical::Center),
+            );
+        }
+
+        container(column![
+            row![
+                container(text("Workshop Cleanup").font(label_font()))
+                    .padding(8)
+                    .style(label_container_style)
+            ],
+            row![
+                scrollable(unused_col).width(Length::FillPortion(2)),
+                scrollable(trash_col).width(Length::FillPortion(1))
+            ]
+            .height(Length::Fill)
+            .padding(5),
+            row![
+                button(text(format!("Move {} to Trash ({})", self.marked.len(), format_size(marked_size))))
+                    .on_press_maybe((!self.marked.is_empty()).then_some(AppMessage::TrashMarked)),
+                button(text("Copy Unsubscribe List"))
+                    .on_press_maybe((!self.marked.is_empty()).then_some(AppMessage::CopyToClip(self.unsubscribe_list()))),
+                button(text("Back to Mods")).on_press(AppMessage::UpdateView(State::LoadedMain)),
+                text(&self.status)
+            ]
+            .spacing(10)
+            .align_y(Vertical::Center)
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_selected_names_are_referenced() {
+        let names_and_details = HashMap::from([
+            ("A".to_string(), ["1".to_string(), String::new(), String::new()]),
+            ("B".to_string(), ["2".to_string(), String::new(), String::new()]),
+            ("C".to_string(), ["3".to_string(), String::new(), String::new()]),
+        ]);
+        let presets = vec![
+            HashMap::from([("A".to_string(), true), ("B".to_string(), false)]),
+            HashMap::from([("C".to_string(), true), ("Gone".to_string(), true)]),
+        ];
+
+        assert_eq!(
+            referenced_ids(&presets, &names_and_details),
+            HashSet::from(["1".to_string(), "3".to_string()])
+        );
+        assert_eq!(trash_dir("/steam/content/108600"), PathBuf::from("/steam/content/zsmm_trash"));
+    }
+}
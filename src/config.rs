@@ -1,16 +1,28 @@
 use std::collections::HashMap;
-use std::env::{consts, home_dir};
-use std::path::Path;
+use std::env::consts;
 use std::process::Command;
-use std::slice;
 use tokio::fs;
 use tokio::fs::File;
 use tokio::io::AsyncReadExt;
 
-use crate::config;
-
 pub const LIN_CONFIG_LOC: &str = "/home/star/.config/zsmm/";
 const OS: &str = consts::OS;
+// Files in the config directory that aren't presets
+pub const RESERVED_CONFIG_FILES: [&str; 9] = [
+    "annotations",
+    "fingerprints",
+    "workshop_location",
+    "server_location",
+    "profiles",
+    "bookmarks",
+    "settings",
+    "thumbnails",
+    "pins",
+];
+
+pub fn is_preset(path: &str) -> bool {
+    !RESERVED_CONFIG_FILES.contains(&path.replace(LIN_CONFIG_LOC, "").as_str())
+}
 
 pub async fn check_config_dir() {
     let directory = match OS {
@@ -128,3 +140,64 @@ pub async fn read_config(file_name: String) -> (Vec<String>, HashMap<String, boo
 
     (mod_id_vec, output_map)
 }
+
+pub async fn load_server_location() -> Option<String> {
+    let config_path: String = LIN_CONFIG_LOC.to_owned() + "server_location";
+
+    match fs::read_to_string(&config_path).await {
+        Ok(text) => Some(text.replace("\n", "")),
+        Err(_err) => None,
+    }
+}
+
+pub async fn save_server_location(server_ini: String) {
+    let _ = fs::write(LIN_CONFIG_LOC.to_owned() + "server_location", server_ini).await;
+}
+
+// <server>.ini -> <server>_SandboxVars.lua, <server>_spawnregions.lua, ...
+pub fn server_file(server_ini: &str, suffix: &str) -> String {
+    let base = server_ini.strip_suffix(".ini").unwrap_or(server_ini);
+    format!("{}_{}", base, suffix)
+}
+
+pub async fn read_text_file(path: String) -> (String, Option<String>) {
+    let content = fs::read_to_string(&path).await.ok();
+    (path, content)
+}
+
+pub async fn write_text_file(path: String, content: String) -> String {
+    match fs::write(&path, content).await {
+        Ok(_) => format!("Saved {}", path),
+        Err(err) => format!("Error writing {} -> Err: {}", path, err),
+    }
+}
+
+// Copies a preset from anywhere into the config directory under the same name
+pub async fn import_preset(path: String) {
+    let Some(name) = std::path::Path::new(&path).file_name() else {
+        return;
+    };
+    let name = name.to_string_lossy().to_string();
+    if !is_preset(&name) {
+        println!("{} is a reserved config file name, not importing", name);
+        return;
+    }
+    if let Err(err) = fs::copy(&path, LIN_CONFIG_LOC.to_owned() + &name).await {
+        println!("Error importing {} -> Err: {}", path, err);
+    }
+}
+
+pub async fn load_bookmarks() -> Vec<String> {
+    match fs::read_to_string(LIN_CONFIG_LOC.to_owned() + "bookmarks").await {
+        Ok(text) => text
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| line.to_string())
+            .collect(),
+        Err(_err) => Vec::new(),
+    }
+}
+
+pub async fn save_bookmarks(bookmarks: Vec<String>) {
+    let _ = fs::write(LIN_CONFIG_LOC.to_owned() + "bookmarks", bookmarks.join("\n")).await;
+}
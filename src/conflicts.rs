@@ -0,0 +1,248 @@
+use crate::custom_theme::{button, label_container_style, label_font, scrollable, text};
+use crate::localmodinfo::collect_files;
+use crate::{AppMessage, State};
+use iced::widget::{column, container, row};
+use iced::Length;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+// Install folders, relative to steamapps, that hold the vanilla media folder
+const VANILLA_MEDIA: [&str; 3] = [
+    "common/ProjectZomboid/projectzomboid/media",
+    "common/ProjectZomboid/media",
+    "common/Project Zomboid Dedicated Server/media",
+];
+
+// One mods/<folder> inside a workshop item, in load order
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModSource {
+    pub workshop_id: String,
+    pub folder: String,
+    pub media: PathBuf,
+}
+
+impl ModSource {
+    pub fn label(&self) -> String {
+        format!("{} ({})", self.folder, self.workshop_id)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileConflict {
+    pub path: String,
+    // Every mod shipping the path in load order, the last one wins
+    pub providers: Vec<String>,
+    pub overrides_vanilla: bool,
+}
+
+impl FileConflict {
+    pub fn winner(&self) -> &str {
+        self.providers.last().map(|provider| provider.as_str()).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConflictReport {
+    pub mods_scanned: usize,
+    pub files_scanned: usize,
+    pub conflicts: Vec<FileConflict>,
+    // Paths shipped by a single mod that replace a vanilla file, with that mod
+    pub vanilla_overrides: Vec<(String, String)>,
+    pub vanilla_dir: Option<String>,
+    pub errors: Vec<String>,
+}
+
+// <library>/steamapps/workshop/content/108600 -> <library>/steamapps/common/.../media
+pub fn vanilla_media_dir(workshop_root: &Path) -> Option<PathBuf> {
+    let steamapps = workshop_root
+        .ancestors()
+        .find(|ancestor| ancestor.file_name().is_some_and(|name| name == "steamapps"))?;
+    VANILLA_MEDIA
+        .iter()
+        .map(|media| steamapps.join(media))
+        .find(|media| media.is_dir())
+}
+
+// Relative media paths use / on every platform so they compare across mods
+pub fn relative_media_path(media: &Path, file: &Path) -> Option<String> {
+    let relative = file.strip_prefix(media).ok()?;
+    let parts: Vec<String> = relative
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy().to_string())
+        .collect();
+    Some(parts.join("/"))
+}
+
+pub fn find_conflicts(
+    index: &BTreeMap<String, Vec<String>>,
+    is_vanilla: impl Fn(&str) -> bool,
+) -> (Vec<FileConflict>, Vec<(String, String)>) {
+    let mut conflicts: Vec<FileConflict> = Vec::new();
+    let mut vanilla_overrides: Vec<(String, String)> = Vec::new();
+
+    for (path, providers) in index {
+        let overrides_vanilla = is_vanilla(path);
+        match providers.len() {
+            0 => {}
+            1 if overrides_vanilla => vanilla_overrides.push((path.clone(), providers[0].clone())),
+            1 => {}
+            _ => conflicts.push(FileConflict {
+                path: path.clone(),
+                providers: providers.clone(),
+                overrides_vanilla,
+            }),
+        }
+    }
+    (conflicts, vanilla_overrides)
+}
+
+pub async fn mod_sources(workshop_location: &str, workshop_ids: &[String]) -> Vec<ModSource> {
+    let mut sources: Vec<ModSource> = Vec::new();
+
+    for workshop_id in workshop_ids {
+        let mods = Path::new(workshop_location).join(workshop_id).join("mods");
+        let Ok(mut entries) = tokio::fs::read_dir(&mods).await else {
+            continue;
+        };
+        let mut folders: Vec<PathBuf> = Vec::new();
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if entry.path().is_dir() {
+                folders.push(entry.path());
+            }
+        }
+        folders.sort();
+        for folder in folders {
+            sources.push(ModSource {
+                workshop_id: workshop_id.clone(),
+                folder: folder
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                media: folder.join("media"),
+            });
+        }
+    }
+    sources
+}
+
+// workshop_ids must already be in load order, see localmodinfo::load_order
+pub async fn detect_conflicts(workshop_location: String, workshop_ids: Vec<String>) -> ConflictReport {
+    let mut report = ConflictReport::default();
+    let mut index: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let sources = mod_sources(&workshop_location, &workshop_ids).await;
+    report.mods_scanned = sources.len();
+
+    for source in &sources {
+        if !source.media.is_dir() {
+            continue;
+        }
+        let mut files: Vec<PathBuf> = Vec::new();
+        if let Err(err) = collect_files(&source.media, &mut files).await {
+            report.errors.push(format!("{} -> {}", source.label(), err));
+        }
+        report.files_scanned += files.len();
+        for file in files {
+            if let Some(path) = relative_media_path(&source.media, &file) {
+                index.entry(path).or_default().push(source.label());
+            }
+        }
+    }
+
+    let vanilla = vanilla_media_dir(Path::new(&workshop_location));
+    if vanilla.is_none() {
+        report
+            .errors
+            .push(String::from("Game install not found next to the workshop folder, vanilla overrides not checked"));
+    }
+    let (conflicts, vanilla_overrides) = find_conflicts(&index, |path| {
+        vanilla.as_ref().is_some_and(|media| media.join(path).is_file())
+    });
+    report.conflicts = conflicts;
+    report.vanilla_overrides = vanilla_overrides;
+    report.vanilla_dir = vanilla.map(|media| media.to_string_lossy().to_string());
+    report
+}
+
+impl ConflictReport {
+    pub fn conflict_report_view(&self) -> iced::widget::Container<'_, AppMessage> {
+        let mut conflict_col = column![text(format!(
+            "Paths shipped by more than one mod: {}",
+            self.conflicts.len()
+        ))]
+        .spacing(6);
+        for conflict in &self.conflicts {
+            let vanilla = match conflict.overrides_vanilla {
+                true => " (also replaces vanilla)",
+                false => "",
+            };
+            conflict_col = conflict_col.push(column![
+                text(format!("media/{}{}", conflict.path, vanilla)).font(label_font()),
+                text(format!("  Wins: {}", conflict.winner())),
+                text(format!(
+                    "  Overridden: {}",
+                    conflict.providers[..conflict.providers.len() - 1].join(", ")
+                ))
+            ]);
+        }
+
+        let mut vanilla_col = column![text(format!(
+            "Vanilla files replaced by a single mod: {}",
+            self.vanilla_overrides.len()
+        ))];
+        for (path, provider) in &self.vanilla_overrides {
+            vanilla_col = vanilla_col.push(text(format!("media/{} <- {}", path, provider)));
+        }
+        for error in &self.errors {
+            vanilla_col = vanilla_col.push(text(error));
+        }
+
+        container(column![
+            row![
+                container(text("File Conflicts").font(label_font()))
+                    .padding(8)
+                    .style(label_container_style)
+            ],
+            text(format!(
+                "Scanned {} files in {} mods, later mods in the load order win. Vanilla media: {}",
+                self.files_scanned,
+                self.mods_scanned,
+                self.vanilla_dir.as_deref().unwrap_or("not found")
+            )),
+            row![
+                scrollable(conflict_col).width(Length::FillPortion(1)),
+                scrollable(vanilla_col).width(Length::FillPortion(1))
+            ]
+            .height(Length::Fill)
+            .padding(5),
+            row![button(text("Back to Mods")).on_press(AppMessage::UpdateView(State::LoadedMain))]
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn later_mod_wins_and_vanilla_overrides_are_split_out() {
+        let index = BTreeMap::from([
+            (
+                "lua/shared/Items.lua".to_string(),
+                vec!["A (1)".to_string(), "B (2)".to_string()],
+            ),
+            ("lua/client/ISUI.lua".to_string(), vec!["B (2)".to_string()]),
+            ("textures/own.png".to_string(), vec!["A (1)".to_string()]),
+        ]);
+        let (conflicts, vanilla) =
+            find_conflicts(&index, |path| path.starts_with("lua/client") || path.ends_with("Items.lua"));
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].winner(), "B (2)");
+        assert!(conflicts[0].overrides_vanilla);
+        assert_eq!(vanilla, vec![("lua/client/ISUI.lua".to_string(), "B (2)".to_string())]);
+        assert_eq!(
+            relative_media_path(Path::new("/w/mods/A/media"), Path::new("/w/mods/A/media/lua/a.lua")),
+            Some("lua/a.lua".to_string())
+        );
+    }
+}
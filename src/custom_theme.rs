@@ -1,8 +1,96 @@
-use iced_core::{color,Color, Background, border, Border, Shadow};
-use iced::Font;
+use iced_core::{Color, Background, border, Border, Shadow};
+use iced::{Element, Font};
+use iced::Theme;
+use iced::widget::{Button, Checkbox, Scrollable, Text, TextInput};
+use iced::widget::text::IntoFragment;
+use std::cell::Cell;
+use iced::widget::{button as iced_button, checkbox as iced_checkbox};
+use iced::widget::{scrollable as iced_scrollable, text_input as iced_text_input};
 use iced::font::{Family, Weight, Stretch, Style};
+use iced::theme::Palette;
+
+// Every view builds its text, buttons, checkboxes, scrollables and text inputs
+// through the helpers at the bottom, so each widget picks up the styles below
+const HIGH_CONTRAST_NAME: &str = "ZSMM High Contrast";
+
+// Font and text size from Settings, set by view() before any widget is built so
+// a change shows up on the next frame instead of the next start
+thread_local! {
+    static TEXT_STYLE: Cell<(Font, u16)> = const { Cell::new((Font::DEFAULT, 16)) };
+}
+
+pub fn set_text_style(font: Font, size: u16) {
+    TEXT_STYLE.with(|style| style.set((font, size)));
+}
+
+pub fn text_font() -> Font {
+    TEXT_STYLE.with(|style| style.get().0)
+}
+
+pub fn text_size() -> u16 {
+    TEXT_STYLE.with(|style| style.get().1)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThemeChoice {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl ThemeChoice {
+    pub const ALL: [ThemeChoice; 3] = [ThemeChoice::Dark, ThemeChoice::Light, ThemeChoice::HighContrast];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ThemeChoice::Dark => "dark",
+            ThemeChoice::Light => "light",
+            ThemeChoice::HighContrast => "high_contrast",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<ThemeChoice> {
+        ThemeChoice::ALL.into_iter().find(|choice| choice.name() == name)
+    }
+
+    pub fn theme(&self) -> Theme {
+        match self {
+            ThemeChoice::Dark => Theme::KanagawaDragon,
+            ThemeChoice::Light => Theme::custom(
+                String::from("ZSMM Light"),
+                Palette {
+                    background: Color::from_rgb8(242, 239, 233),
+                    text: Color::from_rgb8(33, 31, 29),
+                    primary: Color::from_rgb8(70, 104, 146),
+                    success: Color::from_rgb8(66, 128, 70),
+                    danger: Color::from_rgb8(178, 58, 52),
+                },
+            ),
+            ThemeChoice::HighContrast => Theme::custom(
+                String::from(HIGH_CONTRAST_NAME),
+                Palette {
+                    background: Color::BLACK,
+                    text: Color::WHITE,
+                    primary: Color::from_rgb8(255, 214, 0),
+                    success: Color::from_rgb8(0, 255, 110),
+                    danger: Color::from_rgb8(255, 70, 70),
+                },
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for ThemeChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ThemeChoice::Dark => "Dark",
+            ThemeChoice::Light => "Light",
+            ThemeChoice::HighContrast => "High Contrast",
+        })
+    }
+}
 
-//TODO: Make custom theme for application
 pub fn label_font() -> iced::Font {
     Font {
         family: Family::Monospace,
@@ -11,12 +99,13 @@ pub fn label_font() -> iced::Font {
         style: Style::Normal,
     }
 }
-pub fn scroll_container_style() -> iced::widget::container::Style {
+
+fn framed_style(background: Color, text: Color, frame: Color) -> iced::widget::container::Style {
     iced::widget::container::Style{
-        text_color: Some(Color::from_rgb8(255, 255, 255)),
-        background: Some(Background::Color(Color::from_rgb8(98, 94, 90))),
+        text_color: Some(text),
+        background: Some(Background::Color(background)),
         border: Border {
-            color: Color::from_rgb8(0, 0, 0),
+            color: frame,
             width: 5.0,
             radius: border::Radius {
                 ..Default::default()
@@ -29,21 +118,145 @@ pub fn scroll_container_style() -> iced::widget::container::Style {
         },
     }
 }
-pub fn label_container_style() -> iced::widget::container::Style {
-    iced::widget::container::Style{
-        text_color: Some(Color::from_rgb8(255, 255, 255)),
-        background: Some(Background::Color(Color::from_rgb8(98, 94, 90))),
+pub fn scroll_container_style(theme: &Theme) -> iced::widget::container::Style {
+    let palette = theme.extended_palette();
+    framed_style(
+        palette.background.weak.color,
+        palette.background.weak.text,
+        palette.background.strong.color,
+    )
+}
+pub fn label_container_style(theme: &Theme) -> iced::widget::container::Style {
+    let palette = theme.extended_palette();
+    framed_style(
+        palette.background.strong.color,
+        palette.background.strong.text,
+        palette.background.base.text,
+    )
+}
+pub fn selected_row_style(theme: &Theme) -> iced::widget::container::Style {
+    let palette = theme.extended_palette();
+    iced::widget::container::Style {
+        text_color: Some(palette.primary.weak.text),
+        background: Some(Background::Color(palette.primary.weak.color)),
+        ..Default::default()
+    }
+}
+// Checked mods get a primary border, the focused one the selected row fill
+pub fn card_style(theme: &Theme, checked: bool, focused: bool) -> iced::widget::container::Style {
+    let palette = theme.extended_palette();
+    let background = match focused {
+        true => palette.primary.weak,
+        false => palette.background.weak,
+    };
+    iced::widget::container::Style {
+        text_color: Some(background.text),
+        background: Some(Background::Color(background.color)),
         border: Border {
-            color: Color::from_rgb8(0, 0, 0),
-            width: 5.0,
-            radius: border::Radius {
-                ..Default::default()
+            color: match checked {
+                true => palette.primary.strong.color,
+                false => palette.background.strong.color,
             },
+            width: 2.0,
+            radius: border::Radius::from(4.0),
         },
-        shadow: Shadow {
-            color: Color::from_rgb8(0, 0, 0),
-            offset: iced_core::Vector { x: 0.0, y: 0.0 },
-            blur_radius: 0.0,
+        ..Default::default()
+    }
+}
+
+// High contrast widgets get thicker outlines in the text color
+fn outline_width(theme: &Theme) -> f32 {
+    match theme.to_string() == HIGH_CONTRAST_NAME {
+        true => 2.0,
+        false => 1.0,
+    }
+}
+pub fn button_style(theme: &Theme, status: iced_button::Status) -> iced_button::Style {
+    let palette = theme.extended_palette();
+    let base = iced_button::primary(theme, status);
+    iced_button::Style {
+        border: Border {
+            color: match status {
+                iced_button::Status::Disabled => palette.background.strong.color,
+                iced_button::Status::Hovered | iced_button::Status::Pressed => palette.background.base.text,
+                iced_button::Status::Active => palette.primary.strong.color,
+            },
+            width: outline_width(theme),
+            radius: border::Radius::from(2.0),
         },
+        ..base
     }
 }
+pub fn checkbox_style(theme: &Theme, status: iced_checkbox::Status) -> iced_checkbox::Style {
+    let palette = theme.extended_palette();
+    let base = iced_checkbox::primary(theme, status);
+    let hovered = matches!(status, iced_checkbox::Status::Hovered { .. });
+    iced_checkbox::Style {
+        border: Border {
+            color: match hovered {
+                true => palette.primary.strong.color,
+                false => palette.background.base.text,
+            },
+            width: outline_width(theme),
+            ..base.border
+        },
+        text_color: Some(palette.background.base.text),
+        ..base
+    }
+}
+pub fn scrollable_style(theme: &Theme, status: iced_scrollable::Status) -> iced_scrollable::Style {
+    let palette = theme.extended_palette();
+    let mut style = iced_scrollable::default(theme, status);
+    for rail in [&mut style.vertical_rail, &mut style.horizontal_rail] {
+        rail.border = Border {
+            color: palette.background.strong.color,
+            width: outline_width(theme) - 1.0,
+            radius: border::Radius::from(2.0),
+        };
+        // The resting scroller has to stand out from the rail behind it
+        if rail.scroller.color == palette.background.strong.color {
+            rail.scroller.color = palette.background.base.text.scale_alpha(0.6);
+        }
+    }
+    style
+}
+pub fn text_input_style(theme: &Theme, status: iced_text_input::Status) -> iced_text_input::Style {
+    let palette = theme.extended_palette();
+    let base = iced_text_input::default(theme, status);
+    iced_text_input::Style {
+        border: Border {
+            color: match status {
+                iced_text_input::Status::Focused => palette.primary.strong.color,
+                iced_text_input::Status::Hovered => palette.background.base.text,
+                _ => palette.background.strong.color,
+            },
+            width: outline_width(theme),
+            ..base.border
+        },
+        placeholder: palette.background.base.text.scale_alpha(0.6),
+        ..base
+    }
+}
+
+// Drop in replacements for iced's widget helpers with the styles above applied
+pub fn text<'a>(content: impl IntoFragment<'a>) -> Text<'a> {
+    Text::new(content).font(text_font()).size(text_size())
+}
+pub fn button<'a, Message>(content: impl Into<Element<'a, Message>>) -> Button<'a, Message> {
+    Button::new(content).style(button_style)
+}
+pub fn checkbox<'a, Message>(label: impl Into<String>, is_checked: bool) -> Checkbox<'a, Message> {
+    Checkbox::new(label, is_checked)
+        .font(text_font())
+        .text_size(text_size())
+        .style(checkbox_style)
+}
+pub fn scrollable<'a, Message>(content: impl Into<Element<'a, Message>>) -> Scrollable<'a, Message> {
+    Scrollable::new(content).style(scrollable_style)
+}
+pub fn text_input<'a, Message: Clone>(placeholder: &str, value: &str) -> TextInput<'a, Message> {
+    TextInput::new(placeholder, value)
+        .font(text_font())
+        .size(text_size())
+        .style(text_input_style)
+}
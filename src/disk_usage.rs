@@ -0,0 +1,171 @@
+use crate::custom_theme::{button, label_container_style, label_font, scrollable, text};
+use crate::localmodinfo::{collect_files, format_size};
+use crate::{AppMessage, State};
+use iced::widget::{column, container, row};
+use iced::{Length, alignment::Vertical};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SizeSort {
+    #[default]
+    Size,
+    Name,
+}
+
+// Sizes are keyed on workshop ID, a workshop item is what joining players download
+#[derive(Debug, Clone)]
+pub struct DiskUsage {
+    pub sizes: HashMap<String, u64>,
+    pub sort: SizeSort,
+    pub descending: bool,
+}
+
+impl Default for DiskUsage {
+    fn default() -> Self {
+        DiskUsage {
+            sizes: HashMap::new(),
+            sort: SizeSort::Size,
+            descending: true,
+        }
+    }
+}
+
+impl DiskUsage {
+    pub fn size_of(&self, workshop_id: &str) -> Option<u64> {
+        self.sizes.get(workshop_id).copied()
+    }
+
+    pub fn size_label(&self, workshop_id: &str) -> String {
+        match self.size_of(workshop_id) {
+            Some(size) => format_size(size),
+            None => String::from("..."),
+        }
+    }
+
+    pub fn total(&self, workshop_ids: &[String]) -> u64 {
+        workshop_ids.iter().filter_map(|id| self.size_of(id)).sum()
+    }
+
+    // Clicking the active column flips the direction, a new column starts largest/A first
+    pub fn sort_by(&mut self, sort: SizeSort) {
+        match self.sort == sort {
+            true => self.descending = !self.descending,
+            false => {
+                self.sort = sort;
+                self.descending = sort == SizeSort::Size;
+            }
+        }
+    }
+
+    // rows are (mod name, workshop ID, selected)
+    pub fn sorted(&self, mut rows: Vec<(String, String, bool)>) -> Vec<(String, String, bool)> {
+        match self.sort {
+            SizeSort::Size => rows.sort_by_key(|(_name, id, _selected)| self.size_of(id).unwrap_or_default()),
+            SizeSort::Name => rows.sort_by_key(|(name, _id, _selected)| name.to_lowercase()),
+        }
+        if self.descending {
+            rows.reverse();
+        }
+        rows
+    }
+
+    // selected_ids holds each workshop item once, several selected mods can share one item
+    pub fn largest_mods_view(
+        &self,
+        rows: Vec<(String, String, bool)>,
+        selected_ids: Vec<String>,
+    ) -> iced::widget::Container<'_, AppMessage> {
+        let total: u64 = self.sizes.values().sum();
+        let arrow = |sort: SizeSort| match (self.sort == sort, self.descending) {
+            (false, _) => "",
+            (true, true) => " v",
+            (true, false) => " ^",
+        };
+
+        let mut list = column![row![
+            button(text(format!("Size{}", arrow(SizeSort::Size))))
+                .width(Length::Fixed(120.0))
+                .on_press(AppMessage::SortSizes(SizeSort::Size)),
+            button(text(format!("Mod{}", arrow(SizeSort::Name))))
+                .on_press(AppMessage::SortSizes(SizeSort::Name))
+        ]
+        .spacing(10)];
+        for (name, id, selected) in self.sorted(rows) {
+            let marker = match selected {
+                true => "[x]",
+                false => "[ ]",
+            };
+            list = list.push(
+                row![
+                    text(self.size_label(&id)).width(Length::Fixed(120.0)),
+                    text(marker).font(label_font()),
+                    text(format!("{} ({})", name, id))
+                ]
+                .spacing(10)
+                .align_y(Vertical::Center),
+            );
+        }
+
+        container(column![
+            row![
+                container(text("Largest Mods").font(label_font()))
+                    .padding(8)
+                    .style(label_container_style)
+            ],
+            text(format!(
+                "Selected download: {} in {} workshop items, whole workshop folder: {}",
+                format_size(self.total(&selected_ids)),
+                selected_ids.len(),
+                format_size(total)
+            )),
+            scrollable(list.padding(5)).height(Length::Fill),
+            row![button(text("Back to Mods")).on_press(AppMessage::UpdateView(State::LoadedMain))]
+        ])
+    }
+}
+
+pub async fn directory_size(path: &Path) -> u64 {
+    let mut files: Vec<PathBuf> = Vec::new();
+    let _ = collect_files(path, &mut files).await;
+    let mut total = 0;
+    for file in files {
+        if let Ok(metadata) = tokio::fs::metadata(&file).await {
+            total += metadata.len();
+        }
+    }
+    total
+}
+
+pub async fn workshop_sizes(workshop_location: String, workshop_ids: Vec<String>) -> HashMap<String, u64> {
+    let mut sizes: HashMap<String, u64> = HashMap::new();
+    for id in workshop_ids {
+        let size = directory_size(&Path::new(&workshop_location).join(&id)).await;
+        sizes.insert(id, size);
+    }
+    sizes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorts_by_size_and_name() {
+        let mut usage = DiskUsage {
+            sizes: HashMap::from([("1".to_string(), 500), ("2".to_string(), 9000)]),
+            ..Default::default()
+        };
+        let rows = vec![
+            ("beta".to_string(), "1".to_string(), true),
+            ("Alpha".to_string(), "2".to_string(), false),
+        ];
+
+        usage.sort_by(SizeSort::Size);
+        usage.sort_by(SizeSort::Size);
+        assert_eq!(usage.sorted(rows.clone())[0].1, "2");
+        usage.sort_by(SizeSort::Name);
+        assert_eq!(usage.sorted(rows)[0].0, "Alpha");
+        assert_eq!(usage.total(&["1".to_string(), "3".to_string()]), 500);
+    }
+}
@@ -1,43 +1,91 @@
 #[allow(unused_import_braces)]
-use crate::AppMessage;
-use iced::widget::{button, column, container, row, scrollable, text, text_input};
+use crate::{AppMessage, State};
+use crate::mounts::{MountPoint, mount_free_space, read_mounts};
+use crate::settings::TextOptions;
+use crate::workshop_root::WorkshopStatus;
+use iced::widget::{column, container, row};
+use crate::custom_theme::{button, checkbox, scrollable, text, text_input};
 use iced::{
-    Background, Border, Color, Element, Font, Length, Renderer, Task,
+    Element, Length, Renderer, Task,
     alignment::{Horizontal, Vertical},
-    color,
 };
-use iced_core::{Shadow, Theme, border::Radius};
+use iced_core::Theme;
 use std::env::home_dir;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+pub const EXPLORER_INPUT_ID: &str = "explorer-path";
+const RECENT_LIMIT: usize = 10;
+
+// What the explorer was opened to pick, the caller gets the result back
+// through AppMessage::PickerChosen with the same purpose
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PickerPurpose {
+    #[default]
+    WorkshopRoot,
+    ServerIni,
+    ImportPreset,
+    OutputDirectory,
+    VanillaMap,
+    LocalMods,
+}
+
+impl PickerPurpose {
+    pub fn title(&self) -> &'static str {
+        match self {
+            PickerPurpose::WorkshopRoot => "Choose the workshop folder",
+            PickerPurpose::ServerIni => "Choose a server ini",
+            PickerPurpose::ImportPreset => "Choose a preset to import",
+            PickerPurpose::OutputDirectory => "Choose an output folder",
+            PickerPurpose::VanillaMap => "Choose the vanilla map folder, media/maps/Muldraugh, KY",
+            PickerPurpose::LocalMods => "Choose the local mods folder, e.g. ~/Zomboid/mods",
+        }
+    }
+
+    pub fn picks_files(&self) -> bool {
+        matches!(self, PickerPurpose::ServerIni | PickerPurpose::ImportPreset)
+    }
+
+    // An empty filter lists every file, presets are written without an extension
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            PickerPurpose::ServerIni => &["ini"],
+            _ => &[],
+        }
+    }
+
+    fn accepts(&self, path: &Path) -> bool {
+        self.extensions().is_empty()
+            || path
+                .extension()
+                .is_some_and(|extension| self.extensions().contains(&extension.to_string_lossy().as_ref()))
+    }
+}
+
 #[derive(Debug)]
 pub struct Explorer<'a> {
     pub os: &'a str,
     pub current_path: PathBuf,
     pub previous_path: PathBuf,
-    pub mounted_drives: Vec<String>,
+    pub mounted_drives: Vec<MountPoint>,
     pub input_buffer: String,
     pub ls_cwd: Vec<String>,
+    pub files: Vec<String>,
+    pub purpose: PickerPurpose,
+    pub return_view: Option<State>,
+    pub highlighted: Option<usize>,
+    pub error: Option<String>,
+    pub show_hidden: bool,
+    pub completions: Vec<String>,
+    pub recent: Vec<String>,
+    pub bookmarks: Vec<String>,
+    // None while the background check of the current folder runs
+    pub workshop_status: Option<WorkshopStatus>,
+    pub confirm_select: bool,
     pub text_options: TextOptions,
 }
 
-#[derive(Debug)]
-pub struct TextOptions {
-    buffer_size: u16,
-    ui_size: u16,
-}
-
-impl Default for TextOptions {
-    fn default() -> Self {
-        TextOptions {
-            buffer_size: 18,
-            ui_size: 18,
-        }
-    }
-}
-
 impl<'a> Default for Explorer<'a> {
     fn default() -> Self {
         let mut state = Explorer {
@@ -47,6 +95,17 @@ impl<'a> Default for Explorer<'a> {
             mounted_drives: Vec::new(),
             input_buffer: String::new(),
             ls_cwd: Vec::new(),
+            files: Vec::new(),
+            purpose: PickerPurpose::default(),
+            return_view: None,
+            highlighted: None,
+            error: None,
+            show_hidden: false,
+            completions: Vec::new(),
+            recent: Vec::new(),
+            bookmarks: Vec::new(),
+            workshop_status: None,
+            confirm_select: false,
             text_options: TextOptions::default(),
         };
 
@@ -57,43 +116,161 @@ impl<'a> Default for Explorer<'a> {
         if state.os != "macos" {
             Explorer::find_mounted_drives(&mut state);
         } else {
-            state.mounted_drives.push(String::from("/"));
+            state.mounted_drives.push(MountPoint::from_path(String::from("/")));
         }
         state
     }
 }
 
 impl<'a> Explorer<'a> {
-    pub fn return_directory(&mut self) {
-        let mut current_directory = self.current_path.to_str().unwrap().to_string();
-        let chop_position = current_directory.rfind("/").unwrap();
-        current_directory.replace_range(chop_position.., "");
-        if current_directory.is_empty() {
-            current_directory = String::from("/")
+    pub fn open(&mut self, purpose: PickerPurpose, return_view: Option<State>) {
+        self.purpose = purpose;
+        self.return_view = return_view;
+        self.list_directory(Some(self.current_path.to_string_lossy().to_string()));
+    }
+    // Only the workshop root picker shows the status, other pickers skip the check
+    pub fn status_task(&self) -> Task<AppMessage> {
+        match self.purpose {
+            PickerPurpose::WorkshopRoot => Task::perform(
+                WorkshopStatus::check_in_background(self.current_path.clone()),
+                AppMessage::WorkshopStatusChecked,
+            ),
+            _ => Task::none(),
+        }
+    }
+    pub fn free_space_task(&self) -> Task<AppMessage> {
+        let paths: Vec<String> = self.mounted_drives.iter().map(|mount| mount.path.clone()).collect();
+        Task::perform(mount_free_space(paths), AppMessage::FreeSpaceReady)
+    }
+    pub fn set_free_space(&mut self, free: Vec<(String, Option<u64>)>) {
+        for (path, bytes) in free {
+            if let Some(mount) = self.mounted_drives.iter_mut().find(|mount| mount.path == path) {
+                mount.free_bytes = bytes;
+            }
         }
-        self.input_buffer = current_directory;
     }
+    pub fn workshop_root_valid(&self) -> bool {
+        self.workshop_status.as_ref().is_some_and(|status| status.is_valid())
+    }
+    pub fn return_directory(&mut self) {
+        self.input_buffer = match self.current_path.parent() {
+            Some(parent) => parent.to_string_lossy().to_string(),
+            None => self.current_path.to_string_lossy().to_string(),
+        };
+    }
+    fn is_hidden(path: &Path) -> bool {
+        path.file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+    }
+    // Errors are kept for the view instead of panicking, the current folder stays as it was
     pub fn list_directory(&mut self, default: Option<String>) {
-        let user_input = match default {
-            Some(string) => {
-                self.input_buffer = string;
-                &self.input_buffer
+        if let Some(string) = default {
+            self.input_buffer = string;
+        }
+        let user_input = self.input_buffer.clone();
+
+        let entries = match fs::read_dir(&user_input) {
+            Ok(entries) => entries,
+            Err(err) => {
+                self.error = Some(format!("Can't open {} -> {}", user_input, err));
+                return;
             }
-            None => &self.input_buffer,
         };
         let mut directories: Vec<String> = Vec::new();
-        for entry in fs::read_dir(user_input).unwrap() {
-            let item = entry.unwrap();
-            if item.path().is_dir() {
-                directories.push(item.path().to_str().unwrap().to_string())
-            } else {
+        let mut files: Vec<String> = Vec::new();
+        for item in entries.flatten() {
+            let path = item.path();
+            if !self.show_hidden && Explorer::is_hidden(&path) {
                 continue;
             }
+            if path.is_dir() {
+                directories.push(path.to_string_lossy().to_string())
+            } else if self.purpose.picks_files() && self.purpose.accepts(&path) {
+                files.push(path.to_string_lossy().to_string())
+            }
         }
         directories.sort();
+        files.sort();
 
-        self.current_path = self.input_buffer.clone().into();
+        self.current_path = user_input.clone().into();
         self.ls_cwd = directories;
+        self.files = files;
+        self.highlighted = None;
+        self.error = None;
+        self.completions.clear();
+        self.workshop_status = None;
+        self.confirm_select = false;
+
+        self.recent.retain(|path| *path != user_input);
+        self.recent.insert(0, user_input);
+        self.recent.truncate(RECENT_LIMIT);
+    }
+
+    // Completes the last path component of the input box against existing folders
+    pub fn complete_input(&mut self) {
+        let input = PathBuf::from(&self.input_buffer);
+        let (parent, prefix) = match self.input_buffer.ends_with('/') {
+            true => (input.clone(), String::new()),
+            false => match (input.parent(), input.file_name()) {
+                (Some(parent), Some(name)) => {
+                    (parent.to_path_buf(), name.to_string_lossy().to_string())
+                }
+                _ => return,
+            },
+        };
+        let Ok(entries) = fs::read_dir(&parent) else {
+            return;
+        };
+
+        let mut matches: Vec<String> = entries
+            .flatten()
+            .filter(|item| item.path().is_dir())
+            .map(|item| item.file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with(&prefix))
+            .filter(|name| self.show_hidden || prefix.starts_with('.') || !name.starts_with('.'))
+            .collect();
+        matches.sort();
+
+        let Some(first) = matches.first() else {
+            self.completions.clear();
+            return;
+        };
+        let mut common = first.clone();
+        for name in &matches[1..] {
+            while !name.starts_with(&common) {
+                common.pop();
+            }
+        }
+
+        let mut completed = parent.join(&common).to_string_lossy().to_string();
+        if matches.len() == 1 {
+            completed.push('/');
+            self.completions.clear();
+        } else {
+            self.completions = matches;
+        }
+        self.input_buffer = completed;
+    }
+
+    pub fn add_bookmark(&mut self) {
+        let path = self.current_path.to_string_lossy().to_string();
+        if !self.bookmarks.contains(&path) {
+            self.bookmarks.push(path);
+        }
+    }
+
+    pub fn move_highlight(&mut self, step: isize) {
+        if self.ls_cwd.is_empty() {
+            return;
+        }
+        self.highlighted = match self.highlighted {
+            Some(index) => Some(index.saturating_add_signed(step).min(self.ls_cwd.len() - 1)),
+            None => Some(0),
+        };
+    }
+
+    pub fn highlighted_path(&self) -> Option<String> {
+        self.highlighted.and_then(|index| self.ls_cwd.get(index).cloned())
     }
 
     pub fn directory_explorer(&self) -> iced::widget::Row<'_, AppMessage> {
@@ -101,6 +278,8 @@ impl<'a> Explorer<'a> {
         let mut directory_column = column![];
         let mut remove_portion: String;
 
+        let highlighted = self.highlighted_path();
+
         for dirs in self.ls_cwd.clone() {
             if !self.current_path.to_str().unwrap().ends_with('/') {
                 remove_portion = self.current_path.to_str().unwrap().to_string() + "/";
@@ -112,10 +291,10 @@ impl<'a> Explorer<'a> {
                     <iced::widget::Button<'_, AppMessage, Theme, Renderer> as Into<
                         Element<'_, AppMessage, Theme, Renderer>,
                     >>::into(
-                        button(text(dirs.replace(
-                            &remove_portion,
-                            "",
-                        )))
+                        button(text(match Some(&dirs) == highlighted.as_ref() {
+                            true => format!("> {}", dirs.replace(&remove_portion, "")),
+                            false => dirs.replace(&remove_portion, ""),
+                        }))
                         .on_press(AppMessage::ExplorerButtonPath(dirs.to_string())),
                     ),
                 );
@@ -150,6 +329,21 @@ impl<'a> Explorer<'a> {
         directory_viewer
     }
 
+    fn file_list(&self) -> iced::widget::Column<'_, AppMessage> {
+        let mut file_column = column![text("Files")];
+        for file in &self.files {
+            let name = Path::new(file)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            file_column = file_column.push(
+                button(text(name))
+                    .on_press(AppMessage::PickerChosen(self.purpose, file.clone())),
+            );
+        }
+        file_column
+    }
+
     fn mounted_view(&self) -> iced::widget::Row<'_, AppMessage> {
         let mut mount_view = row![];
         let mut mount_column = column![text("Mount Points")];
@@ -158,19 +352,43 @@ impl<'a> Explorer<'a> {
                 <iced::widget::Button<'_, AppMessage, Theme, Renderer> as Into<
                     Element<'_, AppMessage, Theme, Renderer>,
                 >>::into(
-                    button(text(mount_point.clone()))
-                        .on_press(AppMessage::ExplorerButtonPath(mount_point)),
+                    button(text(mount_point.path.clone()))
+                        .on_press(AppMessage::ExplorerButtonPath(mount_point.path.clone())),
                 ),
             );
+            mount_column = mount_column.push(text(mount_point.summary()).size(12));
+        }
+        mount_column = mount_column.push(text("Bookmarks"));
+        for bookmark in self.bookmarks.clone() {
+            mount_column = mount_column.push(row![
+                button(text(bookmark.clone()))
+                    .on_press(AppMessage::ExplorerButtonPath(bookmark.clone())),
+                button(text("x")).on_press(AppMessage::RemoveBookmark(bookmark))
+            ]);
+        }
+        mount_column = mount_column.push(text("Recent"));
+        for recent in self.recent.clone() {
+            mount_column = mount_column.push(
+                button(text(recent.clone())).on_press(AppMessage::ExplorerButtonPath(recent)),
+            );
         }
         mount_view = mount_view.push(mount_column);
         mount_view
     }
     fn find_mounted_drives(&mut self) {
+        self.mounted_drives = match self.os {
+            "linux" => read_mounts(),
+            _ => self
+                .find_drive_letters()
+                .into_iter()
+                .map(MountPoint::from_path)
+                .collect(),
+        };
+    }
+    fn find_drive_letters(&self) -> Vec<String> {
         let mut output_container: Vec<String> = Vec::new();
 
         let raw_mounts = match self.os {
-            "linux" => Command::new("lsblk").output().expect("Command Success"),
             "windows" => Command::new("wmic logicaldisk get deviceid")
                 .output()
                 .expect("Success"),
@@ -180,7 +398,6 @@ impl<'a> Explorer<'a> {
         };
         let mut raw_string = String::from_utf8(raw_mounts.stdout).unwrap();
         let target = match self.os {
-            "linux" => "/",
             "windows" => ":",
             _ => "Error",
         };
@@ -194,30 +411,44 @@ impl<'a> Explorer<'a> {
 
             match search.find(target) {
                 Some(usize) => {
-                    if target == "/" {
-                        search.replace_range(..usize, "");
-                    } else {
-                        search.replace_range(..usize - 1, "");
-                    }
-                    if !search.contains("/boot") {
-                        output_container.push(search.clone());
-                    }
+                    search.replace_range(..usize - 1, "");
+                    output_container.push(search.clone());
                 }
                 None => {
-                    println!("/ not found, continuing search...");
+                    println!("{} not found, continuing search...", target);
                 }
             };
         }
-        self.mounted_drives = output_container;
+        output_container
     }
     pub fn explorer_view(&self) -> iced::widget::Container<'_, AppMessage> {
+        let mut browser = row![
+            scrollable(
+                container(self.mounted_view(),)
+                    .align_x(Horizontal::Center)
+                    .width(Length::Fixed(180.0))
+            ),
+            scrollable(
+                container(self.directory_explorer())
+                    .align_x(Horizontal::Center)
+                    .width(Length::Fill)
+            )
+        ];
+        if self.purpose.picks_files() {
+            browser = browser.push(
+                scrollable(container(self.file_list()).width(Length::Fixed(260.0)))
+            );
+        }
+
         container(column![
+            row![text(self.purpose.title()).size(self.text_options.ui_size)].padding(5),
             row![
                 column![row![
                     button(text("Home Dir").size(self.text_options.ui_size))
                         .on_press(AppMessage::ExplorerHome),
                     container(
                         text_input(&self.current_path.to_string_lossy(), &self.input_buffer)
+                            .id(iced::widget::text_input::Id::new(EXPLORER_INPUT_ID))
                             .width(Length::Fill)
                             .size(self.text_options.buffer_size)
                             .on_input(AppMessage::ExplorerPathInput)
@@ -226,30 +457,72 @@ impl<'a> Explorer<'a> {
                     .padding(5),
                     button(text("Back").size(self.text_options.ui_size))
                         .padding(5)
-                        .on_press(AppMessage::ExplorerReturn)
-                ]]
+                        .on_press(AppMessage::ExplorerReturn),
+                    button(text("Bookmark").size(self.text_options.ui_size))
+                        .padding(5)
+                        .on_press(AppMessage::AddBookmark),
+                    checkbox("Hidden", self.show_hidden)
+                        .size(self.text_options.ui_size)
+                        .on_toggle(AppMessage::ExplorerToggleHidden)
+                ]
+                .align_y(Vertical::Center)]
             ],
-            column![row![
-                container(self.mounted_view(),)
-                    .align_x(Horizontal::Center)
-                    .width(Length::Fixed(180.0)),
-                scrollable(
-                    container(self.directory_explorer())
-                        .align_x(Horizontal::Center)
-                        .width(Length::Fill)
-                )
-            ]],
-            row![
-                container(
-                    button(text(format!(
-                        "Select Current Directory: {}",
-                        &self.current_path.to_string_lossy()
-                    )))
-                    .on_press(AppMessage::ExplorerExportPath(None))
-                )
-                .align_x(Horizontal::Right)
-                .width(Length::Fill)
-            ]
+            row![text(match (&self.error, self.completions.is_empty()) {
+                (Some(error), _) => error.clone(),
+                (None, false) => format!("Matches: {}", self.completions.join("  ")),
+                (None, true) => String::new(),
+            })]
+            .padding(5),
+            column![browser],
+            self.selection_row()
         ])
     }
+
+    // Selecting a folder that doesn't look like a workshop root needs a second click
+    fn selection_row(&self) -> iced::widget::Row<'_, AppMessage> {
+        let mut status = row![].spacing(10).align_y(Vertical::Center);
+        if self.purpose == PickerPurpose::WorkshopRoot {
+            status = status.push(text(match &self.workshop_status {
+                Some(workshop_status) => workshop_status.summary(),
+                None => String::from("Checking folder..."),
+            }));
+        }
+        if self.purpose == PickerPurpose::WorkshopRoot
+            && let Some(suggestion) = self.workshop_status.as_ref().and_then(|status| status.suggestion.as_ref())
+        {
+            status = status.push(
+                button(text("Go to suggestion"))
+                    .on_press(AppMessage::ExplorerButtonPath(suggestion.clone())),
+            );
+        }
+
+        let select: Element<'_, AppMessage, Theme, Renderer> = match self.confirm_select {
+            true => row![
+                text("Scan this folder anyway?"),
+                button(text("Select Anyway")).on_press(AppMessage::PickerChosen(
+                    self.purpose,
+                    self.current_path.to_string_lossy().to_string()
+                )),
+                button(text("Keep Browsing")).on_press(AppMessage::ExplorerCancelSelect)
+            ]
+            .spacing(10)
+            .align_y(Vertical::Center)
+            .into(),
+            false if self.purpose.picks_files() => text("Pick one of the files listed").into(),
+            false => button(text(format!(
+                "Select Current Directory: {}",
+                &self.current_path.to_string_lossy()
+            )))
+            .on_press(AppMessage::ExplorerSelectCurrent)
+            .into(),
+        };
+
+        row![
+            container(status).width(Length::Fill).padding(5),
+            container(select).align_x(Horizontal::Right),
+            button(text("Cancel")).on_press(AppMessage::PickerCancel)
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center)
+    }
 }
@@ -0,0 +1,143 @@
+use crate::AppMessage;
+use iced::widget::{pick_list, row};
+use crate::custom_theme::{checkbox, text, text_font, text_input, text_size};
+use iced::{Element, Length, Renderer};
+use iced_core::Theme;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldKind {
+    Boolean,
+    Integer(i64, i64),
+    Double(f64, f64),
+    Enum(&'static [&'static str]),
+    Text,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumChoice {
+    pub index: usize,
+    pub label: &'static str,
+}
+
+impl fmt::Display for EnumChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+// Returns the value as it should be written back to the file, or why it can't be
+pub fn validate(kind: FieldKind, input: &str) -> Result<String, String> {
+    let trimmed = input.trim();
+    match kind {
+        FieldKind::Boolean => match trimmed {
+            "true" | "false" => Ok(trimmed.to_string()),
+            _ => Err("expected true or false".to_string()),
+        },
+        FieldKind::Integer(min, max) => match trimmed.parse::<i64>() {
+            Ok(value) if (min..=max).contains(&value) => Ok(value.to_string()),
+            Ok(_) => Err(format!("must be between {} and {}", min, max)),
+            Err(_) => Err("expected a whole number".to_string()),
+        },
+        FieldKind::Double(min, max) => match trimmed.parse::<f64>() {
+            // Written from the parsed value so 1e3 or +1.5 come out as plain Lua numbers
+            Ok(value) if value >= min && value <= max => match value.to_string() {
+                formatted if formatted.contains('.') => Ok(formatted),
+                formatted => Ok(format!("{}.0", formatted)),
+            },
+            Ok(_) => Err(format!("must be between {} and {}", min, max)),
+            Err(_) => Err("expected a number".to_string()),
+        },
+        FieldKind::Enum(labels) => match trimmed.parse::<usize>() {
+            Ok(value) if (1..=labels.len()).contains(&value) => Ok(value.to_string()),
+            _ => Err(format!("must be between 1 and {}", labels.len())),
+        },
+        FieldKind::Text => Ok(input.to_string()),
+    }
+}
+
+// Numbers are compared by value, an untouched 0.10 must not be rewritten as 0.1
+pub fn same_value(kind: FieldKind, current: &str, value: &str) -> bool {
+    match kind {
+        FieldKind::Double(_, _) | FieldKind::Integer(_, _) => {
+            match (current.trim().parse::<f64>(), value.trim().parse::<f64>()) {
+                (Ok(current), Ok(value)) => current == value,
+                _ => current == value,
+            }
+        }
+        _ => current == value,
+    }
+}
+
+// One labelled row of a settings form; validation errors replace the note
+pub fn form_field<'a>(
+    label: &'a str,
+    kind: FieldKind,
+    input: &str,
+    placeholder: &str,
+    note: &'a str,
+    on_change: impl Fn(String) -> AppMessage + 'a,
+) -> Element<'a, AppMessage, Theme, Renderer> {
+    let widget: Element<'a, AppMessage, Theme, Renderer> = match kind {
+        FieldKind::Boolean => checkbox("", input == "true")
+            .on_toggle(move |bool| on_change(bool.to_string()))
+            .into(),
+        FieldKind::Enum(labels) => {
+            let choices: Vec<EnumChoice> = labels
+                .iter()
+                .enumerate()
+                .map(|(index, label)| EnumChoice { index: index + 1, label })
+                .collect();
+            let selected = input
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| choices.get(index.wrapping_sub(1)).cloned());
+            pick_list(choices, selected, move |choice: EnumChoice| {
+                on_change(choice.index.to_string())
+            })
+            .font(text_font())
+            .text_size(text_size())
+            .into()
+        }
+        FieldKind::Integer(..) | FieldKind::Double(..) | FieldKind::Text => {
+            text_input(placeholder, input)
+                .on_input(on_change)
+                .width(Length::Fixed(240.0))
+                .into()
+        }
+    };
+
+    let hint = match validate(kind, input) {
+        Ok(_) => note.to_string(),
+        Err(err) => err,
+    };
+
+    row![
+        text(label).width(Length::Fixed(240.0)),
+        widget,
+        text(hint).width(Length::Fill)
+    ]
+    .spacing(10)
+    .padding(2)
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_ranges() {
+        assert_eq!(validate(FieldKind::Integer(1, 12), "7"), Ok("7".to_string()));
+        assert!(validate(FieldKind::Integer(1, 12), "13").is_err());
+        assert_eq!(validate(FieldKind::Double(0.0, 4.0), "2"), Ok("2.0".to_string()));
+        assert_eq!(validate(FieldKind::Double(0.0, 5000.0), "1e3"), Ok("1000.0".to_string()));
+        assert_eq!(validate(FieldKind::Double(0.0, 4.0), "+1.5"), Ok("1.5".to_string()));
+        assert_eq!(validate(FieldKind::Double(-4.0, 4.0), "-2.50"), Ok("-2.5".to_string()));
+        assert_eq!(validate(FieldKind::Double(0.0, 1.0), "2.5e-1"), Ok("0.25".to_string()));
+        assert!(validate(FieldKind::Double(0.0, 4.0), "nan").is_err());
+        assert!(validate(FieldKind::Enum(&["a", "b"]), "3").is_err());
+        assert!(same_value(FieldKind::Double(0.0, 1.0), "0.10", "0.1"));
+        assert!(!same_value(FieldKind::Text, "0.10", "0.1"));
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+const HISTORY_LIMIT: usize = 100;
+
+// Snapshots of CheckState::values, one per user action, so a bulk change
+// is undone in a single step just like a single checkbox
+#[derive(Debug, Default)]
+pub struct SelectionHistory {
+    undo_stack: Vec<HashMap<String, bool>>,
+    redo_stack: Vec<HashMap<String, bool>>,
+}
+
+impl SelectionHistory {
+    // Call before mutating the selection
+    pub fn record(&mut self, values: &HashMap<String, bool>) {
+        if self.undo_stack.last() == Some(values) {
+            return;
+        }
+        self.undo_stack.push(values.clone());
+        if self.undo_stack.len() > HISTORY_LIMIT {
+            self.undo_stack.remove(0);
+        }
+        self.redo_stack.clear();
+    }
+
+    pub fn undo(&mut self, values: &mut HashMap<String, bool>) -> bool {
+        match self.undo_stack.pop() {
+            Some(previous) => {
+                self.redo_stack.push(std::mem::replace(values, previous));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self, values: &mut HashMap<String, bool>) -> bool {
+        match self.redo_stack.pop() {
+            Some(next) => {
+                self.undo_stack.push(std::mem::replace(values, next));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undo_and_redo_restore_snapshots() {
+        let mut history = SelectionHistory::default();
+        let mut values = HashMap::from([("A".to_string(), true)]);
+
+        history.record(&values);
+        values.insert("A".to_string(), false);
+
+        assert!(history.undo(&mut values));
+        assert!(values["A"]);
+        assert!(history.redo(&mut values));
+        assert!(!values["A"]);
+        assert!(!history.redo(&mut values));
+    }
+}
@@ -0,0 +1,253 @@
+use crate::custom_theme::{button, label_container_style, label_font, scrollable, text};
+use crate::text_encoding::{TextEncoding, decode_text};
+use crate::{AppMessage, State};
+use iced::widget::{column, container, row};
+use iced::{Element, Length, Renderer, Theme};
+use std::collections::HashMap;
+use std::path::Path;
+
+// Characters that split or end a Mods=/WorkshopItems= entry in the server ini
+const BREAKING_ID_CHARACTERS: [char; 4] = [';', ',', '=', '\\'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IssueKind {
+    NoModsFolder,
+    NoMods,
+    EmptyModFolder,
+    MissingModInfo,
+    UnreadableModInfo(String),
+    MissingId,
+    BreakingId(String),
+    MissingPoster(String),
+    UnusualEncoding(TextEncoding),
+}
+
+impl IssueKind {
+    pub fn severity(&self) -> Severity {
+        match self {
+            IssueKind::MissingPoster(_) | IssueKind::EmptyModFolder | IssueKind::UnusualEncoding(_) => {
+                Severity::Warning
+            }
+            _ => Severity::Error,
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            IssueKind::NoModsFolder => String::from("No mods/ folder"),
+            IssueKind::NoMods => String::from("mods/ folder holds no mods"),
+            IssueKind::EmptyModFolder => String::from("Empty mod folder"),
+            IssueKind::MissingModInfo => String::from("mod.info is missing"),
+            IssueKind::UnreadableModInfo(err) => format!("mod.info can't be read -> {}", err),
+            IssueKind::MissingId => String::from("mod.info has no id="),
+            IssueKind::BreakingId(id) => format!("id={} contains characters that break the ini lists", id),
+            IssueKind::MissingPoster(poster) => format!("poster={} does not exist", poster),
+            IssueKind::UnusualEncoding(encoding) => format!("mod.info is encoded as {}", encoding),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthIssue {
+    pub workshop_id: String,
+    pub mod_folder: Option<String>,
+    pub kind: IssueKind,
+}
+
+// Checks one mod.info, has_file answers for paths relative to the mod folder
+pub fn check_mod_info(content: &str, has_file: impl Fn(&str) -> bool) -> Vec<IssueKind> {
+    let mut issues: Vec<IssueKind> = Vec::new();
+    let mut id: Option<&str> = None;
+
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "id" => id = Some(value),
+            "poster" if !value.is_empty() && !has_file(value) => {
+                issues.push(IssueKind::MissingPoster(value.to_string()))
+            }
+            _ => {}
+        }
+    }
+
+    match id {
+        None | Some("") => issues.push(IssueKind::MissingId),
+        // Spaces load fine inside a ;-separated Mods= entry
+        Some(id) if id.contains(BREAKING_ID_CHARACTERS) => {
+            issues.push(IssueKind::BreakingId(id.to_string()))
+        }
+        Some(_) => {}
+    }
+    issues
+}
+
+async fn check_workshop_item(workshop_location: &str, workshop_id: &str) -> Vec<HealthIssue> {
+    let issue = |mod_folder: Option<String>, kind: IssueKind| HealthIssue {
+        workshop_id: workshop_id.to_string(),
+        mod_folder,
+        kind,
+    };
+    let mods = Path::new(workshop_location).join(workshop_id).join("mods");
+    let Ok(mut entries) = tokio::fs::read_dir(&mods).await else {
+        return vec![issue(None, IssueKind::NoModsFolder)];
+    };
+
+    let mut issues: Vec<HealthIssue> = Vec::new();
+    let mut mod_count = 0;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let folder = entry.path();
+        if !folder.is_dir() {
+            continue;
+        }
+        mod_count += 1;
+        let name = entry.file_name().to_string_lossy().to_string();
+
+        let empty = match tokio::fs::read_dir(&folder).await {
+            Ok(mut contents) => matches!(contents.next_entry().await, Ok(None)),
+            Err(_err) => true,
+        };
+        if empty {
+            issues.push(issue(Some(name), IssueKind::EmptyModFolder));
+            continue;
+        }
+
+        let info = folder.join("mod.info");
+        if !info.is_file() {
+            issues.push(issue(Some(name), IssueKind::MissingModInfo));
+            continue;
+        }
+        match tokio::fs::read(&info).await {
+            Ok(bytes) => {
+                let (content, encoding) = decode_text(&bytes);
+                if encoding.is_unusual() {
+                    issues.push(issue(Some(name.clone()), IssueKind::UnusualEncoding(encoding)));
+                }
+                for kind in check_mod_info(&content, |file| folder.join(file).is_file()) {
+                    issues.push(issue(Some(name.clone()), kind));
+                }
+            }
+            Err(err) => issues.push(issue(Some(name), IssueKind::UnreadableModInfo(err.to_string()))),
+        }
+    }
+    if mod_count == 0 {
+        issues.push(issue(None, IssueKind::NoMods));
+    }
+    issues
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct IntegrityReport {
+    pub checked: usize,
+    pub issues: Vec<HealthIssue>,
+}
+
+impl IntegrityReport {
+    pub fn issues_for(&self, workshop_id: &str) -> Vec<&HealthIssue> {
+        self.issues.iter().filter(|issue| issue.workshop_id == workshop_id).collect()
+    }
+
+    // A short marker for the mod list, coloured by the worst issue
+    pub fn badge(&self, workshop_id: &str) -> Option<Element<'_, AppMessage, Theme, Renderer>> {
+        let issues = self.issues_for(workshop_id);
+        let worst = issues.iter().map(|issue| issue.kind.severity()).max()?;
+        let badge = text(format!("!{}", issues.len())).size(12);
+        Some(match worst {
+            Severity::Error => badge.style(iced::widget::text::danger).into(),
+            Severity::Warning => badge.style(iced::widget::text::primary).into(),
+        })
+    }
+
+    pub fn integrity_view<'a>(&'a self, names: &HashMap<String, String>) -> iced::widget::Container<'a, AppMessage> {
+        let errors = self
+            .issues
+            .iter()
+            .filter(|issue| issue.kind.severity() == Severity::Error)
+            .count();
+        let mut by_item: Vec<(&String, Vec<&HealthIssue>)> = Vec::new();
+        for issue in &self.issues {
+            match by_item.iter_mut().find(|(id, _issues)| **id == issue.workshop_id) {
+                Some((_id, issues)) => issues.push(issue),
+                None => by_item.push((&issue.workshop_id, vec![issue])),
+            }
+        }
+
+        let mut report_col = column![].spacing(6);
+        for (workshop_id, issues) in by_item {
+            let mut item_col = column![text(format!(
+                "{} ({})",
+                names.get(workshop_id).map(|name| name.as_str()).unwrap_or("Unknown"),
+                workshop_id
+            ))
+            .font(label_font())];
+            for issue in issues {
+                let line = text(format!(
+                    "  {}{}",
+                    issue.mod_folder.as_ref().map(|folder| format!("{}: ", folder)).unwrap_or_default(),
+                    issue.kind.description()
+                ));
+                item_col = item_col.push(match issue.kind.severity() {
+                    Severity::Error => line.style(iced::widget::text::danger),
+                    Severity::Warning => line.style(iced::widget::text::primary),
+                });
+            }
+            report_col = report_col.push(item_col);
+        }
+
+        container(column![
+            row![
+                container(text("Mod Health").font(label_font()))
+                    .padding(8)
+                    .style(label_container_style)
+            ],
+            text(format!(
+                "Checked {} workshop items: {} errors, {} warnings",
+                self.checked,
+                errors,
+                self.issues.len() - errors
+            )),
+            scrollable(report_col.padding(5)).height(Length::Fill),
+            row![button(text("Back to Mods")).on_press(AppMessage::UpdateView(State::LoadedMain))]
+        ])
+    }
+}
+
+pub async fn check_integrity(workshop_location: String, workshop_ids: Vec<String>) -> IntegrityReport {
+    let mut report = IntegrityReport {
+        checked: workshop_ids.len(),
+        issues: Vec::new(),
+    };
+    for workshop_id in workshop_ids {
+        report.issues.extend(check_workshop_item(&workshop_location, &workshop_id).await);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_missing_id_breaking_id_and_missing_poster() {
+        let has_file = |file: &str| file == "poster.png";
+
+        assert_eq!(check_mod_info("name=Good\nid=Good\nposter=poster.png\n", has_file), vec![]);
+        assert_eq!(
+            check_mod_info("name=Bad\nposter=gone.png\n", has_file),
+            vec![IssueKind::MissingPoster("gone.png".to_string()), IssueKind::MissingId]
+        );
+        assert_eq!(
+            check_mod_info("id=Two;Mods\n", has_file),
+            vec![IssueKind::BreakingId("Two;Mods".to_string())]
+        );
+        assert_eq!(check_mod_info("id=Spaced Out\n", has_file), vec![]);
+    }
+}
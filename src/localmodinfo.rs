@@ -1,7 +1,8 @@
+use crate::text_encoding::decode_text;
 use std::boxed::Box;
 use std::collections::HashMap;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str;
 use tokio::fs;
 use tokio::fs::File;
@@ -81,14 +82,7 @@ pub async fn mod_info_parse(source: String, target: Target) -> io::Result<String
         Target::Name => "name=",
     };
 
-    content = match str::from_utf8(&strbuf) {
-        Ok(content) => content.to_string(),
-        Err(_err) => {
-            println!("Error reading bytes as utf8");
-            "Error".to_string()
-        }
-    };
-
+    content = decode_text(&strbuf).0;
 
     loop {
         if content.contains(&input.to_string()) {
@@ -226,6 +220,61 @@ pub async fn collect_map_names(path: &Path, mapnames: &mut Vec<String>) -> std::
     Ok(())
 }
 
+pub const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+// FNV-1a, unlike std's hashers its output is the same on every toolchain so it can be stored
+pub fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(hash, |hash, byte| (hash ^ *byte as u64).wrapping_mul(FNV_PRIME))
+}
+
+// Blocking read, callers run it through spawn_blocking
+pub fn file_hash(path: &Path) -> Option<u64> {
+    use std::io::Read;
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut buffer = vec![0; 64 * 1024];
+    let mut hash = FNV_OFFSET;
+    loop {
+        match file.read(&mut buffer).ok()? {
+            0 => return Some(hash),
+            read => hash = fnv1a(hash, &buffer[..read]),
+        }
+    }
+}
+
+pub fn format_size(bytes: u64) -> String {
+    let units = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < units.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{} {}", bytes, units[0]),
+        _ => format!("{:.1} {}", size, units[unit]),
+    }
+}
+
+//=== Function for recursively collecting every file below a directory =====
+
+pub async fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if path.is_dir() && let Ok(mut entry) = fs::read_dir(path).await {
+        while let Some(dir_entry) = entry.next_entry().await? {
+            let next_path = dir_entry.path();
+
+            if next_path.is_dir() {
+                let _ = Box::pin(collect_files(&next_path, files)).await;
+            } else {
+                files.push(next_path);
+            }
+        }
+    }
+    Ok(())
+}
+
 pub async fn names_and_posters(
     initial_path: String,
     workshop_ids: Vec<String>,
@@ -266,28 +315,47 @@ pub async fn collect_workshop_ids(workshop_location: String) -> Vec<String> {
     id_vec.unwrap()
 }
 
+// Selected workshop items ordered by mod name, the order they're exported to
+// Mods=/WorkshopItems= and so the order later mods override earlier ones in
+pub fn load_order(filter: &HashMap<String, bool>, info: &HashMap<String, [String; 3]>) -> Vec<String> {
+    let mut names: Vec<&String> = filter
+        .iter()
+        .filter(|(_name, selected)| **selected)
+        .map(|(name, _selected)| name)
+        .collect();
+    names.sort();
+
+    let mut workshop_ids: Vec<String> = Vec::new();
+    for name in names {
+        if let Some(details) = info.get(name)
+            && !workshop_ids.contains(&details[0])
+        {
+            workshop_ids.push(details[0].clone());
+        }
+    }
+    workshop_ids
+}
+
 pub async fn collect_selections(
     workshop_location: String,
     filter: HashMap<String, bool>,
     info: HashMap<String, [String; 3]>,
+    pinned: HashMap<String, String>,
 ) -> [Vec<String>; 3] {
-    let mut workshop_ids: Vec<String> = Vec::new();
+    let mut workshop_ids: Vec<String> = load_order(&filter, &info);
     let mut workshop_id_paths: Vec<String> = Vec::new();
     let mut mod_ids: Vec<String> = Vec::new();
     let mut map_ids: Vec<String> = Vec::new();
-    let mod_id_locations: Vec<String>; 
-
-    filter.iter().for_each(|(key, value)| {
-        if value == &true {
-            workshop_ids.push(info.get(key).unwrap()[0].to_string());
-        }
-    });
 
-    workshop_ids.iter().for_each(|id| {
-        workshop_id_paths.push(format!("{}/{}/", workshop_location, id))
+    // Pinned items are read from their local copy and left out of WorkshopItems=
+    // so the server doesn't download the newer workshop version
+    workshop_ids.iter().for_each(|id| match pinned.get(id) {
+        Some(local_path) => workshop_id_paths.push(format!("{}/", local_path)),
+        None => workshop_id_paths.push(format!("{}/{}/", workshop_location, id)),
     });
+    workshop_ids.retain(|id| !pinned.contains_key(id));
 
-    mod_id_locations = match mod_id_path_collecter(workshop_id_paths.clone()).await {
+    let mod_id_locations: Vec<String> = match mod_id_path_collecter(workshop_id_paths.clone()).await {
         Ok(output) => output,
         Err(err) => panic!("error getting mod_id file locations {}", err),
     };
@@ -319,7 +387,7 @@ mod tests {
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
     async fn does_it_work() {
         let path = "/mnt/d1/SSD1/steamapps/workshop/content/108600/2850935956".to_string();
-        let result = mod_file_finder(path, FileType::Png).await;
+        let _result = mod_file_finder(path, FileType::Png).await;
     }
 }
 
@@ -1,9 +1,8 @@
 #![windows_subsystem = "windows"]
 use iced::Length::FillPortion;
 use iced::widget::scrollable::{Direction, Scrollbar};
-use iced::widget::{
-    Scrollable, button, checkbox, column, container, image, row, scrollable, text, text_input,
-};
+use iced::widget::{column, container, image, row};
+use iced::widget::{opaque, responsive, stack};
 use iced::{Element, Renderer, Task};
 #[allow(unused_imports)]
 use iced::{
@@ -13,23 +12,82 @@ use iced::{
 use iced_core::Theme;
 use std::collections::{HashMap, hash_map::Entry};
 use std::env::home_dir;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+pub mod annotations;
+pub mod changes;
+pub mod cleanup;
 pub mod config;
+pub mod conflicts;
 pub mod custom_theme;
+pub mod disk_usage;
 pub mod explorer;
+pub mod form;
+pub mod history;
+pub mod integrity;
 pub mod localmodinfo;
+pub mod map_cells;
+pub mod mounts;
+pub mod pins;
+pub mod profiles;
+pub mod sandbox;
+pub mod sandbox_editor;
+pub mod scripts;
+pub mod server_ini;
+pub mod settings;
+pub mod shortcuts;
+pub mod spawnregions;
+pub mod text_encoding;
+pub mod thumbnails;
+pub mod workshop_root;
+pub use annotations::*;
+pub use changes::*;
+pub use cleanup::*;
 pub use config::*;
+pub use conflicts::*;
 #[allow(unused_imports)]
 pub use custom_theme::*;
+pub use disk_usage::*;
 pub use explorer::*;
+pub use form::*;
+pub use history::*;
+pub use integrity::*;
 pub use localmodinfo::*;
+pub use map_cells::*;
+pub use mounts::*;
+pub use pins::*;
+pub use profiles::*;
+pub use sandbox::*;
+pub use sandbox_editor::*;
+pub use scripts::*;
+pub use server_ini::*;
+pub use settings::*;
+pub use shortcuts::*;
+pub use spawnregions::*;
+pub use text_encoding::*;
+pub use thumbnails::*;
+pub use workshop_root::*;
 
 #[tokio::main]
 async fn main() -> iced::Result {
+    let settings = load_settings();
     iced::application("ZSMM", update, view)
+        .settings(settings.iced_settings())
+        .subscription(subscription)
         .antialiasing(true)
-        .theme(|_s| iced::Theme::KanagawaDragon)
-        .run()
+        .theme(|app| app.settings.theme.theme())
+        .scale_factor(|app| app.settings.scale_factor())
+        .run_with(move || {
+            (
+                ZSMM::with_settings(settings),
+                Task::batch(vec![
+                    Task::perform(load_server_location(), AppMessage::LoadServerLocation),
+                    Task::perform(load_profiles(), AppMessage::ProfilesLoaded),
+                    Task::perform(load_bookmarks(), AppMessage::BookmarksLoaded),
+                    Task::perform(load_annotations(), AppMessage::AnnotationsLoaded),
+                    Task::perform(load_pins(), AppMessage::PinsLoaded),
+                ]),
+            )
+        })
 }
 //TODO: Next updates need to be aimed at decluttering this, explorer
 //      should be able to make use of the same instruction set with path
@@ -50,8 +108,54 @@ pub enum AppMessage {
     ExplorerButtonPath(String),
     ExplorerReturn,
     ExplorerExportPath(Option<String>),
+    ExplorerSelectCurrent,
+    ExplorerCancelSelect,
+    OpenPicker(PickerPurpose),
+    PickerChosen(PickerPurpose, String),
+    PickerCancel,
+    OutputSaved(String),
+    ThemeSelected(ThemeChoice),
+    FontSelected(FontChoice),
+    ToggleContentHashes(bool),
+    AdjustSetting(SizeSetting, i32),
+    ResetScale,
+    ToggleLayout,
+    AnnotationsLoaded(Annotations),
+    TagsInput(String),
+    NoteInput(String),
+    SaveAnnotations,
+    AnnotationsSaved(String),
+    TagFilter(Option<String>),
+    CheckConflicts,
+    ConflictsFound(ConflictReport),
+    IndexScripts,
+    ScriptsIndexed(ScriptIndex),
+    ScriptSearch(String),
+    MapsChecked(MapReport),
+    ExportConfirmed,
+    VanillaMapInput(String),
+    SaveSettings,
+    SizesReady(HashMap<String, u64>),
+    SortSizes(SizeSort),
+    OpenCleanup,
+    CleanupScanned(CleanupScan),
+    CleanupToggle(String, bool),
+    TrashMarked,
+    ItemsTrashed((Vec<String>, String)),
+    RestoreItem(String),
+    ItemRestored(String),
+    IntegrityChecked(IntegrityReport),
+    ChangesDetected(ChangeReport),
+    LocalModsInput(String),
+    PinsLoaded(Pins),
+    PinMod(String),
+    ModPinned(Result<(String, Pin), String>),
+    PinsSaved(String),
+    UnpinMod(String),
+    ModUnpinned((String, Result<String, String>)),
     ModInfoCollected(Vec<String>),
     NamesPosters(Option<HashMap<String, [String; 3]>>),
+    ThumbnailsReady((HashMap<String, String>, String)),
     ModIDChecked(String, bool),
     BeginExportSelections,
     FileNameBox(String),
@@ -59,6 +163,54 @@ pub enum AppMessage {
     FinalSelectionView(Vec<String>),
     SelectionsReady([Vec<String>; 3]),
     CopyToClip(String),
+    LoadServerLocation(Option<String>),
+    ServerLocationInput(String),
+    GenerateSandboxDefaults,
+    SandboxGenerated(SandboxReport),
+    OpenSandboxEditor,
+    SandboxLoaded((String, Option<String>)),
+    SandboxCategory(String),
+    SandboxField(String, String),
+    SandboxSave,
+    SandboxSaved(String),
+    OpenServerIni,
+    ServerIniLoaded((String, Option<String>)),
+    ServerIniCategory(String),
+    ServerIniField(String, String),
+    ServerIniSave,
+    ServerIniSaved(String),
+    OpenSpawnRegions,
+    SpawnRegionsLoaded(SpawnRegionsDocument),
+    SpawnRegionToggled(usize, bool),
+    SpawnRegionsSave,
+    SpawnRegionsSaved(String),
+    ProfilesLoaded(Vec<ServerProfile>),
+    SelectProfile(usize),
+    NewProfile,
+    EditProfile(usize),
+    RemoveProfile(usize),
+    ProfileField(String, String),
+    SaveProfiles,
+    ProfilesSaved(String),
+    Undo,
+    Redo,
+    SearchInput(String),
+    FocusSearch,
+    SaveShortcut,
+    ExportShortcut,
+    Navigate(isize),
+    ToggleCurrent,
+    Activate,
+    Back,
+    ToggleHelp,
+    CloseHelp,
+    ExplorerToggleHidden(bool),
+    ExplorerComplete,
+    WorkshopStatusChecked((PathBuf, WorkshopStatus)),
+    FreeSpaceReady(Vec<(String, Option<u64>)>),
+    AddBookmark,
+    RemoveBookmark(String),
+    BookmarksLoaded(Vec<String>),
 }
 #[derive(Debug, Clone)]
 pub enum State {
@@ -67,8 +219,29 @@ pub enum State {
     LoadedMain,
     InfoCollection,
     FileExplorer,
+    SandboxReport,
+    SandboxEditor,
+    ServerIni,
+    SpawnRegions,
+    Profiles,
+    Settings,
+    Conflicts,
+    Scripts,
+    MapOverlaps,
+    LargestMods,
+    Cleanup,
+    Integrity,
+    Changes,
 }
 
+const SEARCH_ID: &str = "mod-search";
+const FILE_NAME_ID: &str = "preset-file-name";
+const MOD_LIST_ID: &str = "mod-list";
+const OUTPUT_FILE: &str = "zsmm_selections.txt";
+const THUMBNAIL_ICON: f32 = 24.0;
+const CARD_WIDTH: f32 = 170.0;
+const CARD_SPACING: f32 = 8.0;
+
 pub struct ZSMM<'a> {
     os: &'a str, 
     view: Option<State>,
@@ -81,6 +254,34 @@ pub struct ZSMM<'a> {
     exporting: bool,
     file_name: String,
     output_info: Vec<String>,
+    output_status: String,
+    server_location: String,
+    sandbox_report: SandboxReport,
+    sandbox_editor: SandboxEditor,
+    server_ini: ServerIniEditor,
+    spawn_regions: SpawnRegionEditor,
+    profiles: ProfileManager,
+    selection_history: SelectionHistory,
+    search: String,
+    show_help: bool,
+    settings: Settings,
+    thumbnails: HashMap<String, image::Handle>,
+    thumbnail_status: String,
+    annotations: Annotations,
+    tag_input: String,
+    tag_filter: Option<String>,
+    notes_status: String,
+    conflict_report: ConflictReport,
+    script_index: ScriptIndex,
+    map_report: MapReport,
+    disk_usage: DiskUsage,
+    cleanup: Cleanup,
+    integrity: IntegrityReport,
+    changes: ChangeReport,
+    pins: Pins,
+    pin_status: String,
+    // Workshop ID whose copy is still running, pinning again would make a second copy
+    pinning: Option<String>,
 }
 
 #[derive(Default)]
@@ -118,11 +319,44 @@ impl<'a> Default for ZSMM<'a> {
             exporting: false,
             file_name: String::new(),
             output_info: Vec::new(),
+            output_status: String::new(),
+            server_location: String::new(),
+            sandbox_report: SandboxReport::default(),
+            sandbox_editor: SandboxEditor::default(),
+            server_ini: ServerIniEditor::default(),
+            spawn_regions: SpawnRegionEditor::default(),
+            profiles: ProfileManager::default(),
+            selection_history: SelectionHistory::default(),
+            search: String::new(),
+            show_help: false,
+            settings: Settings::default(),
+            thumbnails: HashMap::new(),
+            thumbnail_status: String::new(),
+            annotations: Annotations::default(),
+            tag_input: String::new(),
+            tag_filter: None,
+            notes_status: String::new(),
+            conflict_report: ConflictReport::default(),
+            script_index: ScriptIndex::default(),
+            map_report: MapReport::default(),
+            disk_usage: DiskUsage::default(),
+            cleanup: Cleanup::default(),
+            integrity: IntegrityReport::default(),
+            changes: ChangeReport::default(),
+            pins: Pins::default(),
+            pin_status: String::new(),
+            pinning: None,
         }
     }
 }
 
 impl<'a> ZSMM<'a> {
+    fn with_settings(settings: Settings) -> Self {
+        let mut app = ZSMM::default();
+        app.file_explorer.text_options = settings.text_options();
+        app.settings = settings;
+        app
+    }
     fn cmd(&self, input: String) {
         let copy = format!("echo \"{}\" | wl-copy", &input);
         let mut command = match self.os {
@@ -146,12 +380,105 @@ impl<'a> ZSMM<'a> {
         let _ = command.wait();
         println!("{:?}", &input);
     }
+    // Sorted mod names matching the search box, in the order the list shows them
+    fn visible_mods(&self) -> Vec<String> {
+        let search = self.search.to_lowercase();
+        let mut keys: Vec<String> = self
+            .check_state
+            .values
+            .keys()
+            .filter(|name| name.to_lowercase().contains(&search))
+            .filter(|name| match (&self.tag_filter, self.check_state.names_and_details.get(*name)) {
+                (Some(tag), Some(details)) => self.annotations.has_tag(&details[0], tag),
+                (Some(_tag), None) => false,
+                (None, _) => true,
+            })
+            .cloned()
+            .collect();
+
+        keys.sort();
+        keys
+    }
+    fn select_mod(&mut self, name: &str) {
+        if let Some(details) = self.check_state.names_and_details.get(name) {
+            self.selected_mod = SelectedMod {
+                mod_name: name.to_string(),
+                mod_id: details[0].clone(),
+                mod_image: details[1].clone(),
+                mod_description: details[2].clone(),
+            };
+            self.tag_input = self.annotations.get(&details[0]).tags.join(", ");
+        }
+    }
+    fn size_rows(&self) -> Vec<(String, String, bool)> {
+        self.check_state
+            .names_and_details
+            .iter()
+            .map(|(name, details)| {
+                let selected = self.check_state.values.get(name).copied().unwrap_or_default();
+                (name.clone(), details[0].clone(), selected)
+            })
+            .collect()
+    }
+    fn workshop_names(&self) -> HashMap<String, String> {
+        self.check_state
+            .names_and_details
+            .iter()
+            .map(|(name, details)| (details[0].clone(), name.clone()))
+            .collect()
+    }
+    fn workshop_id_of(&self, name: &str) -> String {
+        self.check_state
+            .names_and_details
+            .get(name)
+            .map(|details| details[0].clone())
+            .unwrap_or_default()
+    }
+    fn selected_workshop_ids(&self) -> Vec<String> {
+        let mut workshop_ids: Vec<String> = Vec::new();
+
+        for (name, bool) in &self.check_state.values {
+            if *bool && let Some(details) = self.check_state.names_and_details.get(name) {
+                workshop_ids.push(details[0].clone());
+            }
+        }
+        workshop_ids.sort();
+        workshop_ids.dedup();
+        workshop_ids
+    }
     fn intial_view(&self) -> iced::widget::Container<'_, AppMessage> {
-        container(row![
+        let mut profile_row = row![text("Profiles:")];
+        for (index, profile) in self.profiles.profiles.iter().enumerate() {
+            let label = match self.profiles.active == Some(index) {
+                true => format!("[{}]", profile.name),
+                false => profile.name.clone(),
+            };
+            profile_row = profile_row.push(
+                <iced::widget::Button<'_, AppMessage, Theme, Renderer> as Into<
+                    Element<'_, AppMessage, Theme, Renderer>,
+                >>::into(button(text(label)).on_press(AppMessage::SelectProfile(index))),
+            );
+        }
+        profile_row = profile_row.push(
+            button(text("Manage Profiles")).on_press(AppMessage::UpdateView(State::Profiles)),
+        );
+        profile_row = profile_row.push(text(&self.profiles.status));
+
+        container(column![profile_row.spacing(5).padding(5), row![
             button(text("Load Config")).on_press(AppMessage::GetConfigs),
+            button(text("Import Preset"))
+                .on_press(AppMessage::OpenPicker(PickerPurpose::ImportPreset)),
             button(text("Rescan Mod Folder")).on_press(AppMessage::Rescan),
-            button(text("Search for Mods")).on_press(AppMessage::UpdateView(State::FileExplorer))
-        ])
+            button(text("Search for Mods"))
+                .on_press(AppMessage::OpenPicker(PickerPurpose::WorkshopRoot)),
+            button(text("Edit Sandbox Settings")).on_press_maybe(
+                (!self.server_location.is_empty()).then_some(AppMessage::OpenSandboxEditor)
+            ),
+            button(text("Edit Server Settings")).on_press_maybe(
+                (!self.server_location.is_empty()).then_some(AppMessage::OpenServerIni)
+            ),
+            button(text("Settings")).on_press(AppMessage::UpdateView(State::Settings))
+        ]])
     }
     fn config_view(&self) -> iced::widget::Container<'_, AppMessage> {
         let mut col = column![];
@@ -173,17 +500,56 @@ impl<'a> ZSMM<'a> {
         container(col)
     }
     //TODO: sort ID's
-    fn loaded_view(&self) -> iced::widget::Container<'_, AppMessage> {
+    fn thumbnail(&self, name: &str, size: f32) -> iced::widget::Container<'_, AppMessage> {
+        match self.thumbnails.get(name) {
+            Some(handle) => container(image(handle.clone()).width(size).height(size)),
+            None => container(text("")).width(size).height(size),
+        }
+    }
+    fn size_text(&self, workshop_id: &str) -> String {
+        match self.pins.is_pinned(workshop_id) {
+            true => format!("{} pinned", self.disk_usage.size_label(workshop_id)),
+            false => self.disk_usage.size_label(workshop_id),
+        }
+    }
+    fn pin_row(&self) -> iced::widget::Row<'_, AppMessage> {
+        let workshop_id = &self.selected_mod.mod_id;
+        if workshop_id.is_empty() {
+            return row![];
+        }
+        let action = match self.pins.get(workshop_id) {
+            Some(pin) => row![
+                text(format!("Pinned: {}", pin.local_path)),
+                button(text("Unpin")).on_press(AppMessage::UnpinMod(workshop_id.clone()))
+            ],
+            None => row![
+                button(text("Pin Local Copy"))
+                    .on_press_maybe(self.pinning.is_none().then(|| AppMessage::PinMod(workshop_id.clone())))
+            ],
+        };
+        action
+            .push(text(&self.pin_status))
+            .spacing(10)
+            .padding(5)
+            .align_y(Vertical::Center)
+    }
+    fn mod_list(&self) -> iced::widget::Column<'_, AppMessage> {
         let mut mod_col = column![];
         let mut mod_row = row![];
 
-        let mut keys: Vec<String> = self.check_state.values.clone().into_keys().collect();
-
-        keys.sort();
-
-        for name in keys {
+        for name in self.visible_mods() {
             let bool = self.check_state.values.get(&name).unwrap();
+            let selected = name == self.selected_mod.mod_name;
+            let workshop_id = self.workshop_id_of(&name);
+            let size = self.size_text(&workshop_id);
+            let badge = self.integrity.badge(&workshop_id);
+            let marker = match selected {
+                true => ">",
+                false => " ",
+            };
 
+            mod_row = mod_row.push(text(marker).font(label_font()));
+            mod_row = mod_row.push(self.thumbnail(&name, THUMBNAIL_ICON));
             mod_row = mod_row.push(
                 <iced::widget::Checkbox<'_, AppMessage, Theme, Renderer> as Into<
                     Element<'_, AppMessage, Theme, Renderer>,
@@ -192,31 +558,175 @@ impl<'a> ZSMM<'a> {
                         .on_toggle(move |bool| AppMessage::ModIDChecked(name.to_string(), bool)),
                 ),
             );
-            mod_col = mod_col.push(mod_row);
+            mod_row = mod_row.push(text(size).size(12));
+            if let Some(badge) = badge {
+                mod_row = mod_row.push(badge);
+            }
+            mod_col = mod_col.push(match selected {
+                true => container(mod_row).style(selected_row_style),
+                false => container(mod_row),
+            });
             mod_row = row![];
         }
+        mod_col
+    }
+    // Cards wrap into as many columns as fit the width the grid is given
+    fn mod_grid(&self, width: f32) -> iced::widget::Column<'_, AppMessage> {
+        let columns = ((width / (CARD_WIDTH + CARD_SPACING)) as usize).max(1);
+        let mut grid = column![].spacing(CARD_SPACING);
+
+        for names in self.visible_mods().chunks(columns) {
+            let mut card_row = row![].spacing(CARD_SPACING);
+            for name in names.iter().cloned() {
+                let bool = *self.check_state.values.get(&name).unwrap();
+                let workshop_id = self.workshop_id_of(&name);
+                let size = self.size_text(&workshop_id);
+                let focused = name == self.selected_mod.mod_name;
+                let card = container(
+                    column![
+                        self.thumbnail(&name, THUMBNAIL_SIZE as f32),
+                        text(name.clone()).size(14),
+                        row![text(format!("{}  {}", workshop_id, size)).size(12)]
+                            .push_maybe(self.integrity.badge(&workshop_id))
+                            .spacing(5),
+                        checkbox("Selected", bool).on_toggle(move |bool| {
+                            AppMessage::ModIDChecked(name.to_string(), bool)
+                        })
+                    ]
+                    .spacing(4)
+                    .align_x(Horizontal::Center),
+                )
+                .width(CARD_WIDTH)
+                .padding(8)
+                .style(move |theme| card_style(theme, bool, focused));
+                card_row = card_row.push(card);
+            }
+            grid = grid.push(card_row);
+        }
+        grid.padding(5)
+    }
+    fn notes_editor(&self) -> iced::widget::Column<'_, AppMessage> {
+        let workshop_id = &self.selected_mod.mod_id;
+        if workshop_id.is_empty() {
+            return column![];
+        }
+        column![
+            text("Tags"),
+            text_input("Comma separated, e.g. QoL, required by event", &self.tag_input)
+                .on_input(AppMessage::TagsInput)
+                .on_submit(AppMessage::SaveAnnotations),
+            text("Notes"),
+            text_input("Anything worth remembering about this mod", &self.annotations.get(workshop_id).note)
+                .on_input(AppMessage::NoteInput)
+                .on_submit(AppMessage::SaveAnnotations),
+            row![
+                button(text("Save Notes")).on_press(AppMessage::SaveAnnotations),
+                text(&self.notes_status)
+            ]
+            .spacing(10)
+            .align_y(Vertical::Center)
+        ]
+        .spacing(4)
+        .padding(5)
+    }
+    fn tag_filters(&self) -> iced::widget::Row<'_, AppMessage> {
+        let mut filters = row![text("Tags:")].spacing(5).align_y(Vertical::Center);
+        let all_label = match self.tag_filter.is_none() {
+            true => "[All]",
+            false => "All",
+        };
+        filters = filters.push(button(text(all_label)).on_press(AppMessage::TagFilter(None)));
+        for tag in self.annotations.all_tags() {
+            let label = match self.tag_filter.as_ref() == Some(&tag) {
+                true => format!("[{}]", tag),
+                false => tag.clone(),
+            };
+            filters = filters.push(button(text(label)).on_press(AppMessage::TagFilter(Some(tag))));
+        }
+        filters
+    }
+    fn loaded_view(&self) -> iced::widget::Container<'_, AppMessage> {
+        let mods: Element<'_, AppMessage> = match self.settings.layout {
+            ModLayout::List => column![scrollable(self.mod_list()).id(iced::widget::scrollable::Id::new(MOD_LIST_ID))].into(),
+            // Columns follow the width the layout actually hands the grid
+            ModLayout::Grid => container(responsive(|size| {
+                scrollable(self.mod_grid(size.width))
+                    .id(iced::widget::scrollable::Id::new(MOD_LIST_ID))
+                    .into()
+            }))
+            .width(FillPortion(3))
+            .into(),
+        };
 
         container(column![
             row![
-                column![scrollable(mod_col)],
+                text_input("Search mods (Ctrl+F)", &self.search)
+                    .id(iced::widget::text_input::Id::new(SEARCH_ID))
+                    .on_input(AppMessage::SearchInput),
+                button(text(match self.settings.layout {
+                    ModLayout::List => "Grid View",
+                    ModLayout::Grid => "List View",
+                }))
+                .on_press(AppMessage::ToggleLayout),
+                button(text("Shortcuts (F1)")).on_press(AppMessage::ToggleHelp)
+            ]
+            .spacing(5)
+            .padding(5),
+            self.tag_filters().padding(5),
+            row![
+                mods,
                 column![scrollable(column![
                     image(&self.selected_mod.mod_image),
                     text(&self.selected_mod.mod_description),
                     text(&self.selected_mod.mod_id),
+                    text(format!("Size: {}", self.disk_usage.size_label(&self.selected_mod.mod_id))),
                     text(&self.selected_mod.mod_name),
+                    self.notes_editor(),
+                    self.pin_row(),
                     button(text("Export Selections")).on_press(AppMessage::ExportSelections)
                 ])]
             ]
             .height(FillPortion(15))
             .padding(5),
             row![
+                button(text("Undo"))
+                    .on_press_maybe(self.selection_history.can_undo().then_some(AppMessage::Undo))
+                    .padding(2),
+                button(text("Redo"))
+                    .on_press_maybe(self.selection_history.can_redo().then_some(AppMessage::Redo))
+                    .padding(2),
                 button(text("Save Preset"))
                     .on_press(AppMessage::BeginExportSelections)
                     .padding(2),
+                button(text("Check Conflicts"))
+                    .on_press(AppMessage::CheckConflicts)
+                    .padding(2),
+                button(text("Script Index"))
+                    .on_press(AppMessage::IndexScripts)
+                    .padding(2),
+                button(text("Largest Mods"))
+                    .on_press(AppMessage::UpdateView(State::LargestMods))
+                    .padding(2),
+                button(text("Cleanup"))
+                    .on_press(AppMessage::OpenCleanup)
+                    .padding(2),
+                button(text(format!("Mod Health ({})", self.integrity.issues.len())))
+                    .on_press(AppMessage::UpdateView(State::Integrity))
+                    .padding(2),
+                button(text(format!("Changes ({})", self.changes.count())))
+                    .on_press(AppMessage::UpdateView(State::Changes))
+                    .padding(2),
+                text(format!(
+                    "Selected: {}",
+                    format_size(self.disk_usage.total(&self.selected_workshop_ids()))
+                ))
+                .size(14),
+                text(&self.thumbnail_status).size(14),
                 match self.exporting {
                     true => {
                         container(
                             text_input("Enter a File name", &self.file_name)
+                                .id(iced::widget::text_input::Id::new(FILE_NAME_ID))
                                 .on_input(AppMessage::FileNameBox)
                                 .on_submit(AppMessage::ExportSelections),
                         )
@@ -287,7 +797,7 @@ impl<'a> ZSMM<'a> {
             row![
                 container(text("Workshop Ids").font(label_font()))
                     .padding(8)
-                    .style(|_| label_container_style())
+                    .style(label_container_style)
             ],
             row![
                 container(
@@ -299,7 +809,7 @@ impl<'a> ZSMM<'a> {
                 .padding(5)
                 .width(800)
                 .height(48)
-                .style(|_| scroll_container_style()),
+                .style(scroll_container_style),
                 container(
                     button(text("Copy to Clipboard"))
                         .on_press_with(|| AppMessage::CopyToClip(self.output_info[0].clone()))
@@ -310,7 +820,7 @@ impl<'a> ZSMM<'a> {
             row![
                 container(text("Mod Ids").font(label_font()))
                     .padding(8)
-                    .style(|_| label_container_style())
+                    .style(label_container_style)
             ],
             row![
                 container(
@@ -322,7 +832,7 @@ impl<'a> ZSMM<'a> {
                 .padding(5)
                 .width(800)
                 .height(48)
-                .style(|_| scroll_container_style()),
+                .style(scroll_container_style),
                 container(
                     button(text("Copy to Clipboard"))
                         .on_press_with(|| AppMessage::CopyToClip(self.output_info[1].clone()))
@@ -333,7 +843,7 @@ impl<'a> ZSMM<'a> {
             row![
                 container(text("Map Ids").font(label_font()))
                     .padding(8)
-                    .style(|_| label_container_style())
+                    .style(label_container_style)
             ],
             row![
                 container(
@@ -345,7 +855,7 @@ impl<'a> ZSMM<'a> {
                 .padding(5)
                 .width(800)
                 .height(48)
-                .style(|_| scroll_container_style()),
+                .style(scroll_container_style),
                 container(
                     button(text("Copy to Clipboard"))
                         .on_press_with(|| AppMessage::CopyToClip(self.output_info[2].clone()))
@@ -353,6 +863,83 @@ impl<'a> ZSMM<'a> {
                 .padding(5)
                 .height(48)
             ],
+            row![
+                container(
+                    text_input(
+                        "Server ini (e.g. ~/Zomboid/Server/servertest.ini)",
+                        &self.server_location
+                    )
+                    .on_input(AppMessage::ServerLocationInput)
+                    .on_submit(AppMessage::GenerateSandboxDefaults)
+                )
+                .padding(5)
+                .width(800),
+                container(
+                    button(text("Browse")).on_press(AppMessage::OpenPicker(PickerPurpose::ServerIni))
+                )
+                .padding(5),
+                container(
+                    button(text("Generate Sandbox Defaults"))
+                        .on_press(AppMessage::GenerateSandboxDefaults)
+                )
+                .padding(5),
+                container(
+                    button(text("Spawn Regions")).on_press(AppMessage::OpenSpawnRegions)
+                )
+                .padding(5)
+            ],
+            row![
+                container(
+                    button(text("Save to Folder"))
+                        .on_press(AppMessage::OpenPicker(PickerPurpose::OutputDirectory))
+                )
+                .padding(5),
+                container(
+                    button(text("Return Home"))
+                        .on_press(AppMessage::UpdateView(State::InitialMain))
+                )
+                .padding(5),
+                text(&self.output_status)
+            ]
+            .align_y(Vertical::Center)
+        ])
+    }
+    fn sandbox_report_view(&self) -> iced::widget::Container<'_, AppMessage> {
+        let report = &self.sandbox_report;
+        let mut added_col = column![text(format!("Added defaults: {}", report.added.len()))];
+        let mut leftover_col =
+            column![text(format!("Left over from removed mods: {}", report.leftover.len()))];
+
+        for option in &report.added {
+            added_col = added_col.push(text(option));
+        }
+        for option in &report.leftover {
+            leftover_col = leftover_col.push(text(option));
+        }
+        for option in &report.skipped {
+            leftover_col = leftover_col.push(text(format!("Skipped, unknown type: {}", option)));
+        }
+        for error in &report.errors {
+            leftover_col = leftover_col.push(text(error));
+        }
+        let status = match report.unchanged {
+            true => String::from("Nothing to add, the file was left as it was"),
+            false => format!("Existing values kept: {}", report.kept),
+        };
+
+        container(column![
+            row![
+                container(text(format!("Sandbox options for {}", report.target)).font(label_font()))
+                    .padding(8)
+                    .style(label_container_style)
+            ],
+            text(status),
+            row![
+                scrollable(added_col).width(Length::FillPortion(1)),
+                scrollable(leftover_col).width(Length::FillPortion(1))
+            ]
+            .height(FillPortion(15))
+            .padding(5),
             row![container(
                 button(text("Return Home")).on_press(AppMessage::UpdateView(State::InitialMain))
             )]
@@ -362,13 +949,31 @@ impl<'a> ZSMM<'a> {
 
 //TODO: Using shell commands to copy final selections to clipboard for more easy access by user
 fn view<'a>(app: &'a ZSMM) -> Element<'a, AppMessage> {
-    match &app.view {
+    set_text_style(app.settings.font.font(), app.settings.text_size);
+    let content: Element<'a, AppMessage> = match &app.view {
         Some(State::InitialMain) => app.intial_view().into(),
         Some(State::ConfigLoad) => app.config_view().into(),
         Some(State::LoadedMain) => app.loaded_view().into(),
         Some(State::InfoCollection) => app.prepare_info_collection_view().into(),
         Some(State::FileExplorer) => app.file_explorer.explorer_view().into(),
+        Some(State::SandboxReport) => app.sandbox_report_view().into(),
+        Some(State::SandboxEditor) => app.sandbox_editor.sandbox_editor_view().into(),
+        Some(State::ServerIni) => app.server_ini.server_ini_view().into(),
+        Some(State::SpawnRegions) => app.spawn_regions.spawn_regions_view().into(),
+        Some(State::Profiles) => app.profiles.profiles_view().into(),
+        Some(State::Settings) => app.settings.settings_view().into(),
+        Some(State::Conflicts) => app.conflict_report.conflict_report_view().into(),
+        Some(State::Scripts) => app.script_index.script_index_view().into(),
+        Some(State::MapOverlaps) => app.map_report.map_report_view().into(),
+        Some(State::LargestMods) => app.disk_usage.largest_mods_view(app.size_rows(), app.selected_workshop_ids()).into(),
+        Some(State::Cleanup) => app.cleanup.cleanup_view(&app.disk_usage).into(),
+        Some(State::Integrity) => app.integrity.integrity_view(&app.workshop_names()).into(),
+        Some(State::Changes) => app.changes.changes_view(&app.workshop_names()).into(),
         None => panic!("no view in state!"),
+    };
+    match app.show_help {
+        true => stack![content, opaque(help_view())].into(),
+        false => content,
     }
 }
 
@@ -394,13 +999,16 @@ fn update(app: &mut ZSMM, message: AppMessage) -> Task<AppMessage> {
             app.workshop_location = string;
         }
         AppMessage::ViewConfigs(collection) => {
-            app.config_opts = collection;
+            app.config_opts = collection.into_iter().filter(|path| is_preset(path)).collect();
             return Task::perform(pass_to_message(State::ConfigLoad), AppMessage::UpdateView);
         }
         AppMessage::LoadConfig(path) => {
             return Task::perform(read_config(path), AppMessage::PreConfigured);
         }
         AppMessage::PreConfigured((vector, hashmap)) => {
+            if !app.check_state.values.is_empty() {
+                app.selection_history.record(&app.check_state.values);
+            }
             app.check_state.values = hashmap;
             return Task::perform(pass_to_message(vector), AppMessage::ModInfoCollected);
         }
@@ -414,7 +1022,7 @@ fn update(app: &mut ZSMM, message: AppMessage) -> Task<AppMessage> {
             app.file_explorer.previous_path = app.file_explorer.current_path.clone();
             app.file_explorer.input_buffer = string;
             app.file_explorer.list_directory(None);
-            app.file_explorer.directory_explorer();
+            return app.file_explorer.status_task();
         }
         AppMessage::ExplorerHome => {
             app.file_explorer.previous_path = PathBuf::new();
@@ -422,18 +1030,288 @@ fn update(app: &mut ZSMM, message: AppMessage) -> Task<AppMessage> {
             app.file_explorer.input_buffer =
                 app.file_explorer.current_path.to_str().unwrap().to_string();
             app.file_explorer.list_directory(None);
-            app.file_explorer.directory_explorer();
+            return app.file_explorer.status_task();
         }
         AppMessage::ExplorerConfirmPath => {
             app.file_explorer.previous_path = app.file_explorer.current_path.clone();
             app.file_explorer.list_directory(None);
-            app.file_explorer.directory_explorer();
+            return app.file_explorer.status_task();
         }
         AppMessage::ExplorerReturn => {
             app.file_explorer.previous_path = app.file_explorer.current_path.clone();
             app.file_explorer.return_directory();
             app.file_explorer.list_directory(None);
-            app.file_explorer.directory_explorer();
+            return app.file_explorer.status_task();
+        }
+        AppMessage::ExplorerSelectCurrent => {
+            let purpose = app.file_explorer.purpose;
+            if purpose != PickerPurpose::WorkshopRoot || app.file_explorer.workshop_root_valid()
+            {
+                let path = app.file_explorer.current_path.to_string_lossy().to_string();
+                return update(app, AppMessage::PickerChosen(purpose, path));
+            }
+            app.file_explorer.confirm_select = true;
+        }
+        AppMessage::OpenPicker(purpose) => {
+            app.file_explorer.open(purpose, app.view.clone());
+            app.view = Some(State::FileExplorer);
+            return Task::batch([app.file_explorer.status_task(), app.file_explorer.free_space_task()]);
+        }
+        AppMessage::PickerCancel => {
+            app.view = app.file_explorer.return_view.take().or(Some(State::InitialMain));
+        }
+        AppMessage::PickerChosen(purpose, path) => {
+            app.view = app.file_explorer.return_view.take().or(Some(State::InitialMain));
+            match purpose {
+                PickerPurpose::WorkshopRoot => {
+                    return update(app, AppMessage::ExplorerExportPath(Some(path)));
+                }
+                PickerPurpose::ServerIni => {
+                    app.server_location = path;
+                    return Task::perform(
+                        save_server_location(app.server_location.clone()),
+                        AppMessage::Terminal,
+                    );
+                }
+                PickerPurpose::ImportPreset => {
+                    return Task::chain(
+                        Task::perform(import_preset(path), AppMessage::Terminal),
+                        Task::done(AppMessage::GetConfigs),
+                    );
+                }
+                PickerPurpose::VanillaMap => {
+                    app.settings.vanilla_map = path;
+                    return Task::perform(save_settings(app.settings.clone()), AppMessage::Terminal);
+                }
+                PickerPurpose::LocalMods => {
+                    app.settings.local_mods = path;
+                    return Task::perform(save_settings(app.settings.clone()), AppMessage::Terminal);
+                }
+                PickerPurpose::OutputDirectory => {
+                    return Task::perform(
+                        write_text_file(
+                            Path::new(&path).join(OUTPUT_FILE).to_string_lossy().to_string(),
+                            output_file_content(&app.output_info),
+                        ),
+                        AppMessage::OutputSaved,
+                    );
+                }
+            }
+        }
+        AppMessage::OutputSaved(status) => {
+            app.output_status = status;
+        }
+        AppMessage::ThemeSelected(theme) => {
+            app.settings.theme = theme;
+            return Task::perform(save_settings(app.settings.clone()), AppMessage::Terminal);
+        }
+        AppMessage::FontSelected(font) => {
+            app.settings.font = font;
+            return Task::perform(save_settings(app.settings.clone()), AppMessage::Terminal);
+        }
+        AppMessage::ToggleContentHashes(bool) => {
+            app.settings.content_hashes = bool;
+            return Task::perform(save_settings(app.settings.clone()), AppMessage::Terminal);
+        }
+        AppMessage::AdjustSetting(setting, steps) => {
+            app.settings.adjust(setting, steps);
+            app.file_explorer.text_options = app.settings.text_options();
+            return Task::perform(save_settings(app.settings.clone()), AppMessage::Terminal);
+        }
+        AppMessage::AnnotationsLoaded(annotations) => {
+            app.annotations = annotations;
+        }
+        AppMessage::TagsInput(input) => {
+            app.annotations.set_tags(&app.selected_mod.mod_id, &input);
+            app.tag_input = input;
+        }
+        AppMessage::NoteInput(note) => {
+            app.annotations.set_note(&app.selected_mod.mod_id, note);
+        }
+        AppMessage::SaveAnnotations => {
+            return Task::perform(
+                save_annotations(app.annotations.clone()),
+                AppMessage::AnnotationsSaved,
+            );
+        }
+        AppMessage::AnnotationsSaved(status) => {
+            app.notes_status = status;
+        }
+        AppMessage::TagFilter(tag) => {
+            app.tag_filter = tag;
+        }
+        AppMessage::CheckConflicts => {
+            let Some(workshop_location) = app.workshop_location.clone() else {
+                return Task::none();
+            };
+            return Task::perform(
+                detect_conflicts(
+                    workshop_location,
+                    load_order(&app.check_state.values, &app.check_state.names_and_details),
+                ),
+                AppMessage::ConflictsFound,
+            );
+        }
+        AppMessage::ConflictsFound(report) => {
+            app.conflict_report = report;
+            return Task::perform(pass_to_message(State::Conflicts), AppMessage::UpdateView);
+        }
+        AppMessage::IndexScripts => {
+            let Some(workshop_location) = app.workshop_location.clone() else {
+                return Task::none();
+            };
+            return Task::perform(
+                index_scripts(
+                    workshop_location,
+                    load_order(&app.check_state.values, &app.check_state.names_and_details),
+                ),
+                AppMessage::ScriptsIndexed,
+            );
+        }
+        AppMessage::ScriptsIndexed(index) => {
+            app.script_index = ScriptIndex {
+                search: std::mem::take(&mut app.script_index.search),
+                ..index
+            };
+            return Task::perform(pass_to_message(State::Scripts), AppMessage::UpdateView);
+        }
+        AppMessage::ScriptSearch(search) => {
+            app.script_index.search = search;
+        }
+        AppMessage::ToggleLayout => {
+            app.settings.layout = match app.settings.layout {
+                ModLayout::List => ModLayout::Grid,
+                ModLayout::Grid => ModLayout::List,
+            };
+            return Task::perform(save_settings(app.settings.clone()), AppMessage::Terminal);
+        }
+        AppMessage::IntegrityChecked(report) => {
+            app.integrity = report;
+        }
+        AppMessage::ChangesDetected(report) => {
+            app.changes = report;
+        }
+        AppMessage::SizesReady(sizes) => {
+            app.disk_usage.sizes = sizes;
+        }
+        AppMessage::SortSizes(sort) => {
+            app.disk_usage.sort_by(sort);
+        }
+        AppMessage::OpenCleanup => {
+            let Some(workshop_location) = app.workshop_location.clone() else {
+                return Task::none();
+            };
+            return Task::perform(
+                scan_unused(
+                    workshop_location,
+                    app.mod_info.mod_id_vec.clone(),
+                    app.check_state.names_and_details.clone(),
+                    app.check_state.values.clone(),
+                    app.profiles.profiles.iter().map(|profile| profile.preset.clone()).collect(),
+                ),
+                AppMessage::CleanupScanned,
+            );
+        }
+        AppMessage::CleanupScanned(scan) => {
+            app.cleanup.marked.retain(|id| scan.unused.iter().any(|(unused, _name)| unused == id));
+            app.cleanup.scan = scan;
+            return Task::perform(pass_to_message(State::Cleanup), AppMessage::UpdateView);
+        }
+        AppMessage::CleanupToggle(id, bool) => {
+            match bool {
+                true => app.cleanup.marked.insert(id),
+                false => app.cleanup.marked.remove(&id),
+            };
+        }
+        AppMessage::TrashMarked => {
+            return Task::perform(
+                move_to_trash(app.workshop_location.clone().unwrap(), app.cleanup.marked_ids()),
+                AppMessage::ItemsTrashed,
+            );
+        }
+        // Trashed items leave the mod list straight away so an export can't reference them
+        AppMessage::ItemsTrashed((moved, status)) => {
+            app.cleanup.status = status;
+            app.mod_info.mod_id_vec.retain(|id| !moved.contains(id));
+            app.check_state.names_and_details.retain(|_name, details| !moved.contains(&details[0]));
+            let names = &app.check_state.names_and_details;
+            app.check_state.values.retain(|name, _bool| names.contains_key(name));
+            app.selection_history.clear();
+            return update(app, AppMessage::OpenCleanup);
+        }
+        AppMessage::RestoreItem(id) => {
+            return Task::perform(
+                restore_from_trash(app.workshop_location.clone().unwrap(), id),
+                AppMessage::ItemRestored,
+            );
+        }
+        AppMessage::ItemRestored(status) => {
+            app.cleanup.status = status;
+            return update(app, AppMessage::OpenCleanup);
+        }
+        AppMessage::VanillaMapInput(path) => {
+            app.settings.vanilla_map = path;
+        }
+        AppMessage::LocalModsInput(path) => {
+            app.settings.local_mods = path;
+        }
+        AppMessage::PinsLoaded(pins) => {
+            app.pins = pins;
+        }
+        AppMessage::PinMod(workshop_id) => {
+            if app.pinning.is_some() || app.pins.is_pinned(&workshop_id) {
+                return Task::none();
+            }
+            app.pin_status = String::from("Copying...");
+            app.pinning = Some(workshop_id.clone());
+            return Task::perform(
+                pin_mod(
+                    app.workshop_location.clone().unwrap(),
+                    workshop_id,
+                    app.settings.local_mods_dir(),
+                ),
+                AppMessage::ModPinned,
+            );
+        }
+        AppMessage::ModPinned(result) => {
+            app.pinning = None;
+            match result {
+                Ok((workshop_id, pin)) if app.pins.is_pinned(&workshop_id) => {
+                    return Task::perform(discard_copy(pin), AppMessage::PinsSaved);
+                }
+                Ok((workshop_id, pin)) => {
+                    let status = format!("Pinned to {}, exports use this copy", pin.local_path);
+                    app.pins.pins.insert(workshop_id, pin);
+                    return Task::perform(save_pins(app.pins.clone(), status), AppMessage::PinsSaved);
+                }
+                Err(status) => app.pin_status = status,
+            }
+        }
+        AppMessage::PinsSaved(status) => {
+            app.pin_status = status;
+        }
+        AppMessage::UnpinMod(workshop_id) => {
+            if let Some(pin) = app.pins.get(&workshop_id).cloned() {
+                app.pin_status = String::from("Removing...");
+                return Task::perform(unpin_mod(workshop_id, pin), AppMessage::ModUnpinned);
+            }
+        }
+        AppMessage::ModUnpinned((workshop_id, result)) => match result {
+            Ok(status) => {
+                app.pins.pins.remove(&workshop_id);
+                return Task::perform(save_pins(app.pins.clone(), status), AppMessage::PinsSaved);
+            }
+            Err(status) => app.pin_status = status,
+        },
+        AppMessage::SaveSettings => {
+            return Task::perform(save_settings(app.settings.clone()), AppMessage::Terminal);
+        }
+        AppMessage::ResetScale => {
+            app.settings.scale_percent = Settings::default().scale_percent;
+            return Task::perform(save_settings(app.settings.clone()), AppMessage::Terminal);
+        }
+        AppMessage::ExplorerCancelSelect => {
+            app.file_explorer.confirm_select = false;
         }
         AppMessage::ExplorerExportPath(workshop_location) => {
             match workshop_location {
@@ -445,6 +1323,8 @@ fn update(app: &mut ZSMM, message: AppMessage) -> Task<AppMessage> {
                     app.workshop_location = Some(string);
                 }
             }
+            // Snapshots from before a rescan or root switch can name mods that are gone
+            app.selection_history.clear();
             return Task::batch(vec![
                 Task::perform(
                     collect_workshop_ids(app.workshop_location.clone().unwrap()),
@@ -470,16 +1350,50 @@ fn update(app: &mut ZSMM, message: AppMessage) -> Task<AppMessage> {
         AppMessage::NamesPosters(hashmap) => {
             app.check_state.names_and_details = hashmap.unwrap();
             app.checkmark_prep();
-            return Task::perform(pass_to_message(State::LoadedMain), AppMessage::UpdateView);
+            let posters: HashMap<String, String> = app
+                .check_state
+                .names_and_details
+                .iter()
+                .map(|(name, details)| (name.clone(), details[1].clone()))
+                .collect();
+            return Task::batch(vec![
+                Task::perform(pass_to_message(State::LoadedMain), AppMessage::UpdateView),
+                Task::perform(build_thumbnails(posters), AppMessage::ThumbnailsReady),
+                Task::perform(
+                    workshop_sizes(
+                        app.workshop_location.clone().unwrap(),
+                        app.mod_info.mod_id_vec.clone(),
+                    ),
+                    AppMessage::SizesReady,
+                ),
+                Task::perform(
+                    check_integrity(
+                        app.workshop_location.clone().unwrap(),
+                        app.mod_info.mod_id_vec.clone(),
+                    ),
+                    AppMessage::IntegrityChecked,
+                ),
+                Task::perform(
+                    detect_changes(
+                        app.workshop_location.clone().unwrap(),
+                        app.mod_info.mod_id_vec.clone(),
+                        app.settings.content_hashes,
+                    ),
+                    AppMessage::ChangesDetected,
+                ),
+            ]);
+        }
+        AppMessage::ThumbnailsReady((thumbnails, status)) => {
+            app.thumbnail_status = status;
+            app.thumbnails = thumbnails
+                .into_iter()
+                .map(|(name, path)| (name, image::Handle::from_path(path)))
+                .collect();
         }
         AppMessage::ModIDChecked(string, _bool) => {
             app.check_state.current_bool = string.clone();
-            app.selected_mod = SelectedMod {
-                mod_name: string.clone(),
-                mod_id: app.check_state.names_and_details.get(&string).unwrap()[0].clone(),
-                mod_image: app.check_state.names_and_details.get(&string).unwrap()[1].clone(),
-                mod_description: app.check_state.names_and_details.get(&string).unwrap()[2].clone(),
-            };
+            app.select_mod(&string);
+            app.selection_history.record(&app.check_state.values);
             match app.check_state.values.entry(string) {
                 Entry::Occupied(mut entry) => match *entry.get() {
                     true => {
@@ -500,7 +1414,26 @@ fn update(app: &mut ZSMM, message: AppMessage) -> Task<AppMessage> {
         AppMessage::FileNameBox(file_name) => {
             app.file_name = file_name;
         }
+        // Overlapping map cells are shown before anything is written
         AppMessage::ExportSelections => {
+            return Task::perform(
+                detect_map_overlaps(
+                    app.workshop_location.clone().unwrap(),
+                    load_order(&app.check_state.values, &app.check_state.names_and_details),
+                    app.settings.vanilla_map.clone(),
+                ),
+                AppMessage::MapsChecked,
+            );
+        }
+        AppMessage::MapsChecked(report) => {
+            let clear = report.overlaps.is_empty();
+            app.map_report = report;
+            return match clear {
+                true => update(app, AppMessage::ExportConfirmed),
+                false => Task::perform(pass_to_message(State::MapOverlaps), AppMessage::UpdateView),
+            };
+        }
+        AppMessage::ExportConfirmed => {
             app.exporting = false;
             return match app.file_name.is_empty() {
                 false => Task::chain(
@@ -509,6 +1442,7 @@ fn update(app: &mut ZSMM, message: AppMessage) -> Task<AppMessage> {
                             app.workshop_location.clone().unwrap(),
                             app.check_state.values.clone(),
                             app.check_state.names_and_details.clone(),
+                            app.pins.local_paths(),
                         ),
                         AppMessage::SelectionsReady,
                     ),
@@ -526,6 +1460,7 @@ fn update(app: &mut ZSMM, message: AppMessage) -> Task<AppMessage> {
                         app.workshop_location.clone().unwrap(),
                         app.check_state.values.clone(),
                         app.check_state.names_and_details.clone(),
+                        app.pins.local_paths(),
                     ),
                     AppMessage::SelectionsReady,
                 ),
@@ -544,10 +1479,352 @@ fn update(app: &mut ZSMM, message: AppMessage) -> Task<AppMessage> {
         AppMessage::CopyToClip(string) => {
             app.cmd(string);
         }
+        AppMessage::LoadServerLocation(location) => {
+            app.server_location = location.unwrap_or_default();
+        }
+        AppMessage::ServerLocationInput(string) => {
+            app.server_location = string;
+        }
+        AppMessage::GenerateSandboxDefaults => {
+            if app.server_location.is_empty() {
+                return Task::none();
+            }
+            return Task::batch(vec![
+                Task::perform(
+                    generate_sandbox_defaults(
+                        app.workshop_location.clone().unwrap(),
+                        app.selected_workshop_ids(),
+                        server_file(&app.server_location, "SandboxVars.lua"),
+                    ),
+                    AppMessage::SandboxGenerated,
+                ),
+                Task::perform(
+                    save_server_location(app.server_location.clone()),
+                    AppMessage::Terminal,
+                ),
+            ]);
+        }
+        AppMessage::SandboxGenerated(report) => {
+            app.sandbox_report = report;
+            return Task::perform(pass_to_message(State::SandboxReport), AppMessage::UpdateView);
+        }
+        AppMessage::OpenSandboxEditor => {
+            return Task::perform(
+                read_text_file(server_file(&app.server_location, "SandboxVars.lua")),
+                AppMessage::SandboxLoaded,
+            );
+        }
+        AppMessage::SandboxLoaded((path, content)) => {
+            app.sandbox_editor.load(path, content);
+            return Task::perform(pass_to_message(State::SandboxEditor), AppMessage::UpdateView);
+        }
+        AppMessage::SandboxCategory(category) => {
+            app.sandbox_editor.category = category;
+        }
+        AppMessage::SandboxField(key, value) => {
+            app.sandbox_editor.inputs.insert(key, value);
+        }
+        AppMessage::SandboxSave => {
+            let invalid = app.sandbox_editor.invalid_fields();
+            if !invalid.is_empty() {
+                app.sandbox_editor.status = format!("Fix invalid values: {}", invalid.join(", "));
+                return Task::none();
+            }
+            app.sandbox_editor.apply();
+            return Task::perform(
+                write_text_file(
+                    app.sandbox_editor.path.clone(),
+                    app.sandbox_editor.document.render(),
+                ),
+                AppMessage::SandboxSaved,
+            );
+        }
+        AppMessage::SandboxSaved(status) => {
+            app.sandbox_editor.status = status;
+        }
+        AppMessage::OpenServerIni => {
+            return Task::perform(
+                read_text_file(app.server_location.clone()),
+                AppMessage::ServerIniLoaded,
+            );
+        }
+        AppMessage::ServerIniLoaded((path, content)) => {
+            app.server_ini.load(path, content);
+            return Task::perform(pass_to_message(State::ServerIni), AppMessage::UpdateView);
+        }
+        AppMessage::ServerIniCategory(category) => {
+            app.server_ini.category = category;
+        }
+        AppMessage::ServerIniField(key, value) => {
+            app.server_ini.inputs.insert(key, value);
+        }
+        AppMessage::ServerIniSave => {
+            let invalid = app.server_ini.invalid_fields();
+            if !invalid.is_empty() {
+                app.server_ini.status = format!("Fix invalid values: {}", invalid.join(", "));
+                return Task::none();
+            }
+            app.server_ini.apply();
+            return Task::perform(
+                write_text_file(app.server_ini.path.clone(), app.server_ini.document.render()),
+                AppMessage::ServerIniSaved,
+            );
+        }
+        AppMessage::ServerIniSaved(status) => {
+            app.server_ini.status = status;
+        }
+        AppMessage::OpenSpawnRegions => {
+            if app.server_location.is_empty() {
+                return Task::none();
+            }
+            app.spawn_regions.path = server_file(&app.server_location, "spawnregions.lua");
+            app.spawn_regions.status = String::new();
+            return Task::batch(vec![
+                Task::perform(
+                    load_spawn_regions(
+                        app.spawn_regions.path.clone(),
+                        app.workshop_location.clone().unwrap(),
+                        app.selected_workshop_ids(),
+                    ),
+                    AppMessage::SpawnRegionsLoaded,
+                ),
+                Task::perform(
+                    save_server_location(app.server_location.clone()),
+                    AppMessage::Terminal,
+                ),
+            ]);
+        }
+        AppMessage::SpawnRegionsLoaded(document) => {
+            app.spawn_regions.document = document;
+            return Task::perform(pass_to_message(State::SpawnRegions), AppMessage::UpdateView);
+        }
+        AppMessage::SpawnRegionToggled(index, bool) => {
+            if let Some(region) = app.spawn_regions.document.regions.get_mut(index) {
+                region.enabled = bool;
+            }
+        }
+        AppMessage::SpawnRegionsSave => {
+            return Task::perform(
+                write_text_file(
+                    app.spawn_regions.path.clone(),
+                    app.spawn_regions.document.render(),
+                ),
+                AppMessage::SpawnRegionsSaved,
+            );
+        }
+        AppMessage::SpawnRegionsSaved(status) => {
+            app.spawn_regions.status = status;
+        }
+        AppMessage::ProfilesLoaded(profiles) => {
+            app.profiles.profiles = profiles;
+        }
+        AppMessage::SelectProfile(index) => {
+            let Some(profile) = app.profiles.profiles.get(index).cloned() else {
+                return Task::none();
+            };
+            // read_config panics on a missing file, a renamed or deleted preset only gets a status
+            let preset_path = LIN_CONFIG_LOC.to_owned() + &profile.preset;
+            if !profile.preset.is_empty() && !Path::new(&preset_path).is_file() {
+                app.profiles.status = format!(
+                    "Preset {} of {} no longer exists, pick another one under Manage Profiles",
+                    profile.preset, profile.name
+                );
+                return Task::none();
+            }
+            app.profiles.status = String::new();
+            app.profiles.active = Some(index);
+            app.server_location = profile.server_ini.clone();
+            app.check_state.values = HashMap::new();
+            app.selection_history.clear();
+
+            let Some(root) = profile.workshop_root() else {
+                return Task::none();
+            };
+            app.workshop_location = Some(root.clone());
+            return match profile.preset.is_empty() {
+                false => Task::perform(
+                    read_config(preset_path),
+                    AppMessage::PreConfigured,
+                ),
+                true => Task::perform(pass_to_message(Some(root)), AppMessage::ExplorerExportPath),
+            };
+        }
+        AppMessage::NewProfile => {
+            app.profiles.profiles.push(ServerProfile {
+                name: format!("Server {}", app.profiles.profiles.len() + 1),
+                workshop_roots: app.workshop_location.clone().into_iter().collect(),
+                server_ini: app.server_location.clone(),
+                game_build: String::from("41"),
+                preset: app.file_name.clone(),
+            });
+            app.profiles.editing = Some(app.profiles.profiles.len() - 1);
+        }
+        AppMessage::EditProfile(index) => {
+            app.profiles.editing = Some(index);
+        }
+        AppMessage::RemoveProfile(index) => {
+            if index < app.profiles.profiles.len() {
+                app.profiles.profiles.remove(index);
+                app.profiles.editing = None;
+                app.profiles.active = None;
+            }
+        }
+        AppMessage::ProfileField(field, value) => {
+            if let Some(index) = app.profiles.editing
+                && let Some(profile) = app.profiles.profiles.get_mut(index)
+            {
+                profile.set_field(&field, value);
+            }
+        }
+        AppMessage::SaveProfiles => {
+            return Task::perform(
+                save_profiles(app.profiles.profiles.clone()),
+                AppMessage::ProfilesSaved,
+            );
+        }
+        AppMessage::ProfilesSaved(status) => {
+            app.profiles.status = status;
+        }
+        AppMessage::Undo => {
+            if let Some(State::LoadedMain) = app.view {
+                app.selection_history.undo(&mut app.check_state.values);
+            }
+        }
+        AppMessage::Redo => {
+            if let Some(State::LoadedMain) = app.view {
+                app.selection_history.redo(&mut app.check_state.values);
+            }
+        }
+        AppMessage::SearchInput(string) => {
+            app.search = string;
+        }
+        AppMessage::FocusSearch => {
+            if let Some(State::LoadedMain) = app.view {
+                return iced::widget::text_input::focus(iced::widget::text_input::Id::new(SEARCH_ID));
+            }
+        }
+        AppMessage::SaveShortcut => {
+            if let Some(State::LoadedMain) = app.view {
+                if app.exporting && !app.file_name.is_empty() {
+                    return update(app, AppMessage::ExportSelections);
+                }
+                app.exporting = true;
+                return iced::widget::text_input::focus(iced::widget::text_input::Id::new(FILE_NAME_ID));
+            }
+        }
+        AppMessage::ExportShortcut => {
+            if let Some(State::LoadedMain) = app.view {
+                return update(app, AppMessage::ExportSelections);
+            }
+        }
+        AppMessage::Navigate(step) => match app.view {
+            Some(State::LoadedMain) => {
+                let visible = app.visible_mods();
+                if visible.is_empty() {
+                    return Task::none();
+                }
+                let current = visible
+                    .iter()
+                    .position(|name| *name == app.selected_mod.mod_name);
+                let position = match current {
+                    Some(index) => index.saturating_add_signed(step).min(visible.len() - 1),
+                    None => 0,
+                };
+                app.select_mod(&visible[position]);
+                return iced::widget::scrollable::snap_to(
+                    iced::widget::scrollable::Id::new(MOD_LIST_ID),
+                    iced::widget::scrollable::RelativeOffset {
+                        x: 0.0,
+                        y: position as f32 / (visible.len().max(2) - 1) as f32,
+                    },
+                );
+            }
+            Some(State::FileExplorer) => app.file_explorer.move_highlight(step),
+            _ => {}
+        },
+        AppMessage::ToggleCurrent => {
+            if let Some(State::LoadedMain) = app.view
+                && app.check_state.values.contains_key(&app.selected_mod.mod_name)
+            {
+                let name = app.selected_mod.mod_name.clone();
+                return update(app, AppMessage::ModIDChecked(name, true));
+            }
+        }
+        AppMessage::Activate => {
+            if let Some(State::FileExplorer) = app.view
+                && let Some(path) = app.file_explorer.highlighted_path()
+            {
+                return update(app, AppMessage::ExplorerButtonPath(path));
+            }
+        }
+        AppMessage::Back => {
+            if let Some(State::FileExplorer) = app.view {
+                return update(app, AppMessage::ExplorerReturn);
+            }
+        }
+        AppMessage::ToggleHelp => {
+            app.show_help = !app.show_help;
+        }
+        AppMessage::CloseHelp => {
+            app.show_help = false;
+        }
+        AppMessage::ExplorerToggleHidden(bool) => {
+            app.file_explorer.show_hidden = bool;
+            app.file_explorer.input_buffer =
+                app.file_explorer.current_path.to_string_lossy().to_string();
+            app.file_explorer.list_directory(None);
+            return app.file_explorer.status_task();
+        }
+        AppMessage::FreeSpaceReady(free) => {
+            app.file_explorer.set_free_space(free);
+        }
+        AppMessage::WorkshopStatusChecked((path, status)) => {
+            // A result for a folder the user already left is stale
+            if path == app.file_explorer.current_path {
+                app.file_explorer.workshop_status = Some(status);
+            }
+        }
+        AppMessage::ExplorerComplete => {
+            if let Some(State::FileExplorer) = app.view {
+                app.file_explorer.complete_input();
+                return Task::batch(vec![
+                    iced::widget::text_input::focus(iced::widget::text_input::Id::new(EXPLORER_INPUT_ID)),
+                    iced::widget::text_input::move_cursor_to_end(iced::widget::text_input::Id::new(EXPLORER_INPUT_ID)),
+                ]);
+            }
+        }
+        AppMessage::AddBookmark => {
+            app.file_explorer.add_bookmark();
+            return Task::perform(
+                save_bookmarks(app.file_explorer.bookmarks.clone()),
+                AppMessage::Terminal,
+            );
+        }
+        AppMessage::RemoveBookmark(path) => {
+            app.file_explorer.bookmarks.retain(|bookmark| *bookmark != path);
+            return Task::perform(
+                save_bookmarks(app.file_explorer.bookmarks.clone()),
+                AppMessage::Terminal,
+            );
+        }
+        AppMessage::BookmarksLoaded(bookmarks) => {
+            app.file_explorer.bookmarks = bookmarks;
+        }
     }
     Task::none()
 }
 
+// The three output boxes as they'd be pasted into the server ini
+fn output_file_content(output_info: &[String]) -> String {
+    match output_info {
+        [workshop_ids, mod_ids, map_ids] => format!(
+            "WorkshopItems={}\nMods={}\nMap={}\n",
+            workshop_ids, mod_ids, map_ids
+        ),
+        _ => String::new(),
+    }
+}
+
 pub async fn format_output(output_array: [Vec<String>; 3]) -> Vec<String> {
     let mut workshop_ids = String::new();
     let mut mod_ids = String::new();
@@ -0,0 +1,203 @@
+use crate::conflicts::vanilla_media_dir;
+use crate::localmodinfo::{FileType, collect_map_names, mod_file_finder};
+use crate::custom_theme::{button, label_container_style, label_font, scrollable, text};
+use crate::{AppMessage, State};
+use iced::widget::{column, container, row};
+use iced::Length;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub const VANILLA_MAP_NAME: &str = "Muldraugh, KY";
+pub const VANILLA_LABEL: &str = "Vanilla";
+const SAMPLE_CELLS: usize = 8;
+
+// x_y.lotheader -> (x, y)
+pub fn parse_cell(file_name: &str) -> Option<(i32, i32)> {
+    let (x, y) = file_name.strip_suffix(".lotheader")?.split_once('_')?;
+    Some((x.parse().ok()?, y.parse().ok()?))
+}
+
+pub async fn map_cells(map_dir: &Path) -> Vec<(i32, i32)> {
+    let mut cells: Vec<(i32, i32)> = Vec::new();
+    let Ok(mut entries) = tokio::fs::read_dir(map_dir).await else {
+        return cells;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        if let Some(cell) = parse_cell(&entry.file_name().to_string_lossy()) {
+            cells.push(cell);
+        }
+    }
+    cells.sort();
+    cells
+}
+
+// Every pair of maps sharing cells, with how many and a few of them
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapOverlap {
+    pub maps: Vec<String>,
+    pub cells: Vec<(i32, i32)>,
+}
+
+impl MapOverlap {
+    pub fn involves_vanilla(&self) -> bool {
+        self.maps.iter().any(|map| map == VANILLA_LABEL)
+    }
+}
+
+pub fn find_overlaps(maps: &[(String, Vec<(i32, i32)>)]) -> Vec<MapOverlap> {
+    let mut owners: BTreeMap<(i32, i32), Vec<String>> = BTreeMap::new();
+    for (label, cells) in maps {
+        for cell in cells {
+            let owner = owners.entry(*cell).or_default();
+            if !owner.contains(label) {
+                owner.push(label.clone());
+            }
+        }
+    }
+
+    let mut grouped: BTreeMap<Vec<String>, Vec<(i32, i32)>> = BTreeMap::new();
+    for (cell, mut labels) in owners {
+        if labels.len() > 1 {
+            labels.sort();
+            grouped.entry(labels).or_default().push(cell);
+        }
+    }
+    grouped
+        .into_iter()
+        .map(|(maps, cells)| MapOverlap { maps, cells })
+        .collect()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MapReport {
+    pub maps: Vec<(String, usize)>,
+    pub overlaps: Vec<MapOverlap>,
+    pub vanilla_map: Option<String>,
+}
+
+impl MapReport {
+    pub fn map_report_view(&self) -> iced::widget::Container<'_, AppMessage> {
+        let mut map_col = column![text(format!("Maps checked: {}", self.maps.len()))];
+        for (label, cells) in &self.maps {
+            map_col = map_col.push(text(format!("{}: {} cells", label, cells)));
+        }
+        map_col = map_col.push(text(match &self.vanilla_map {
+            Some(path) => format!("Vanilla cells from {}", path),
+            None => String::from("No vanilla map found, set one in Settings to compare against it"),
+        }));
+
+        let mut overlap_col = column![text(format!("Overlapping map pairs: {}", self.overlaps.len()))].spacing(6);
+        for overlap in &self.overlaps {
+            let sample: Vec<String> = overlap
+                .cells
+                .iter()
+                .take(SAMPLE_CELLS)
+                .map(|(x, y)| format!("{}_{}", x, y))
+                .collect();
+            overlap_col = overlap_col.push(column![
+                text(overlap.maps.join(" / ")).font(label_font()),
+                text(format!("  {} shared cells: {}", overlap.cells.len(), sample.join(", ")))
+            ]);
+        }
+
+        container(column![
+            row![
+                container(text("Map Cell Overlaps").font(label_font()))
+                    .padding(8)
+                    .style(label_container_style)
+            ],
+            text("Maps that share cells overwrite each other and can corrupt the world"),
+            row![
+                scrollable(overlap_col).width(Length::FillPortion(1)),
+                scrollable(map_col).width(Length::FillPortion(1))
+            ]
+            .height(Length::Fill)
+            .padding(5),
+            row![
+                button(text("Export Anyway")).on_press(AppMessage::ExportConfirmed),
+                button(text("Back to Mods")).on_press(AppMessage::UpdateView(State::LoadedMain))
+            ]
+            .spacing(10)
+        ])
+    }
+}
+
+// The mod folder export enables for an item, the one holding the first mod.info found
+async fn selected_mod_folder(item: &Path) -> Option<PathBuf> {
+    let mods = item.join("mods");
+    let mod_info = mod_file_finder(mods.to_string_lossy().to_string(), FileType::ModInfo).await;
+    let folder = Path::new(&mod_info).strip_prefix(&mods).ok()?.components().next()?;
+    Some(mods.join(folder))
+}
+
+// Only the maps export writes to Map= are compared, read from the selected mod
+// folder so variants of one map in other folders of the item don't overlap themselves.
+// An empty vanilla_map falls back to the install found next to the workshop folder
+pub async fn detect_map_overlaps(
+    workshop_location: String,
+    workshop_ids: Vec<String>,
+    vanilla_map: String,
+) -> MapReport {
+    let mut report = MapReport::default();
+    let mut maps: Vec<(String, Vec<(i32, i32)>)> = Vec::new();
+
+    for workshop_id in &workshop_ids {
+        let item = Path::new(&workshop_location).join(workshop_id);
+        let Some(folder) = selected_mod_folder(&item).await else {
+            continue;
+        };
+        let mut map_names: Vec<String> = Vec::new();
+        let _ = collect_map_names(&item, &mut map_names).await;
+        map_names.sort();
+        map_names.dedup();
+
+        let folder_name = folder
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        for name in map_names {
+            let cells = map_cells(&folder.join("media").join("maps").join(&name)).await;
+            if !cells.is_empty() {
+                maps.push((format!("{} [{} ({})]", name, folder_name, workshop_id), cells));
+            }
+        }
+    }
+    report.maps = maps.iter().map(|(label, cells)| (label.clone(), cells.len())).collect();
+
+    let vanilla = match vanilla_map.trim() {
+        "" => vanilla_media_dir(Path::new(&workshop_location))
+            .map(|media| media.join("maps").join(VANILLA_MAP_NAME))
+            .filter(|map| map.is_dir()),
+        configured => Some(PathBuf::from(configured)),
+    };
+    if let Some(vanilla) = vanilla {
+        maps.push((VANILLA_LABEL.to_string(), map_cells(&vanilla).await));
+        report.vanilla_map = Some(vanilla.to_string_lossy().to_string());
+    }
+
+    report.overlaps = find_overlaps(&maps);
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_shared_cells_by_map_pair() {
+        assert_eq!(parse_cell("35_32.lotheader"), Some((35, 32)));
+        assert_eq!(parse_cell("35_32.lotpack"), None);
+
+        let maps = vec![
+            ("A".to_string(), vec![(1, 1), (1, 2)]),
+            ("B".to_string(), vec![(1, 2), (5, 5)]),
+            (VANILLA_LABEL.to_string(), vec![(5, 5), (9, 9)]),
+        ];
+        let overlaps = find_overlaps(&maps);
+
+        assert_eq!(overlaps.len(), 2);
+        assert_eq!(overlaps[0].maps, vec!["A", "B"]);
+        assert_eq!(overlaps[0].cells, vec![(1, 2)]);
+        assert!(overlaps[1].involves_vanilla());
+    }
+}
@@ -0,0 +1,197 @@
+use crate::localmodinfo::format_size;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+// Kernel and runtime filesystems that never hold a workshop folder
+const PSEUDO_FILESYSTEMS: [&str; 22] = [
+    "proc", "sysfs", "devtmpfs", "devpts", "tmpfs", "cgroup", "cgroup2", "pstore", "bpf",
+    "securityfs", "debugfs", "tracefs", "configfs", "fusectl", "mqueue", "hugetlbfs", "autofs",
+    "binfmt_misc", "efivarfs", "nsfs", "rpc_pipefs", "squashfs",
+];
+const PSEUDO_PREFIXES: [&str; 5] = ["/proc", "/sys", "/dev", "/boot", "/snap"];
+// A stale network mount never answers statvfs, its free space stays unknown
+const FREE_SPACE_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MountPoint {
+    pub path: String,
+    pub source: String,
+    pub fs_type: String,
+    pub label: Option<String>,
+    pub free_bytes: Option<u64>,
+}
+
+impl MountPoint {
+    pub fn from_path(path: String) -> MountPoint {
+        MountPoint {
+            path,
+            source: String::new(),
+            fs_type: String::new(),
+            label: None,
+            free_bytes: None,
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        let mut details: Vec<String> = Vec::new();
+        if let Some(label) = &self.label {
+            details.push(label.clone());
+        }
+        if !self.fs_type.is_empty() {
+            details.push(self.fs_type.clone());
+        }
+        details.push(match self.free_bytes {
+            Some(free) => format!("{} free", format_size(free)),
+            None => String::from("? free"),
+        });
+        details.join(" | ")
+    }
+}
+
+// mountinfo escapes spaces, tabs, newlines and backslashes as \ooo octal
+fn unescape(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut output: Vec<u8> = Vec::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        let octal = bytes
+            .get(index + 1..index + 4)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+        if bytes[index] == b'\\'
+            && let Some(value) = octal
+        {
+            output.push(value);
+            index += 4;
+        } else {
+            output.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8_lossy(&output).to_string()
+}
+
+pub fn parse_mountinfo(content: &str) -> Vec<MountPoint> {
+    let mut mounts: Vec<MountPoint> = Vec::new();
+
+    for line in content.lines() {
+        let Some((mount, filesystem)) = line.split_once(" - ") else {
+            continue;
+        };
+        let Some(path) = mount.split(' ').nth(4).map(unescape) else {
+            continue;
+        };
+        let mut filesystem = filesystem.split(' ');
+        let fs_type = filesystem.next().unwrap_or_default().to_string();
+        let source = filesystem.next().map(unescape).unwrap_or_default();
+
+        let pseudo = PSEUDO_FILESYSTEMS.contains(&fs_type.as_str())
+            || PSEUDO_PREFIXES
+                .iter()
+                .any(|prefix| path == *prefix || path.starts_with(&format!("{}/", prefix)))
+            // /run is runtime state apart from removable media and FUSE mounts
+            // such as GVFS network shares or the Flatpak document portal
+            || (path.starts_with("/run/")
+                && !path.starts_with("/run/media/")
+                && !fs_type.starts_with("fuse"));
+        if pseudo || mounts.iter().any(|known| known.path == path) {
+            continue;
+        }
+
+        mounts.push(MountPoint {
+            path,
+            source,
+            fs_type,
+            label: None,
+            free_bytes: None,
+        });
+    }
+    mounts
+}
+
+// /dev/disk/by-label/<label> links point at the device a mount's source names
+fn device_labels() -> HashMap<PathBuf, String> {
+    let mut labels: HashMap<PathBuf, String> = HashMap::new();
+    if let Ok(entries) = fs::read_dir("/dev/disk/by-label") {
+        for entry in entries.flatten() {
+            if let Ok(device) = fs::canonicalize(entry.path()) {
+                labels.insert(device, unescape(&entry.file_name().to_string_lossy()));
+            }
+        }
+    }
+    labels
+}
+
+#[cfg(unix)]
+fn free_space(path: &str) -> Option<u64> {
+    let c_path = std::ffi::CString::new(path).ok()?;
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: c_path is a valid NUL terminated string and stats is a valid out pointer
+    match unsafe { libc::statvfs(c_path.as_ptr(), &mut stats) } {
+        0 => Some(stats.f_bavail as u64 * stats.f_frsize as u64),
+        _ => None,
+    }
+}
+
+#[cfg(not(unix))]
+fn free_space(_path: &str) -> Option<u64> {
+    None
+}
+
+// Queried after the window is up, each mount on its own blocking thread
+pub async fn mount_free_space(paths: Vec<String>) -> Vec<(String, Option<u64>)> {
+    let queries: Vec<(String, tokio::task::JoinHandle<Option<u64>>)> = paths
+        .into_iter()
+        .map(|path| {
+            let queried = path.clone();
+            (path, tokio::task::spawn_blocking(move || free_space(&queried)))
+        })
+        .collect();
+
+    let mut free: Vec<(String, Option<u64>)> = Vec::new();
+    for (path, query) in queries {
+        let bytes = match tokio::time::timeout(FREE_SPACE_TIMEOUT, query).await {
+            Ok(Ok(bytes)) => bytes,
+            _ => None,
+        };
+        free.push((path, bytes));
+    }
+    free
+}
+
+pub fn read_mounts() -> Vec<MountPoint> {
+    let content = fs::read_to_string("/proc/self/mountinfo").unwrap_or_default();
+    let labels = device_labels();
+    let mut mounts = parse_mountinfo(&content);
+
+    for mount in &mut mounts {
+        mount.label = fs::canonicalize(&mount.source)
+            .ok()
+            .and_then(|device| labels.get(&device).cloned());
+    }
+    if mounts.is_empty() {
+        mounts.push(MountPoint::from_path(String::from("/")));
+    }
+    mounts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_pseudo_filesystems_and_unescapes_paths() {
+        let content = "23 28 0:22 / /proc rw,relatime - proc proc rw\n\
+            28 1 259:2 / / rw,relatime - ext4 /dev/nvme0n1p2 rw\n\
+            40 28 259:3 / /mnt/Steam\\040Games rw,relatime - ntfs3 /dev/sda1 rw\n\
+            41 28 0:50 / /mnt/share rw,relatime - cifs //nas/share rw\n\
+            42 28 0:51 / /run/user/1000 rw - tmpfs tmpfs rw\n";
+        let mounts = parse_mountinfo(content);
+        let paths: Vec<&str> = mounts.iter().map(|mount| mount.path.as_str()).collect();
+
+        assert_eq!(paths, vec!["/", "/mnt/Steam Games", "/mnt/share"]);
+        assert_eq!(mounts[2].fs_type, "cifs");
+    }
+}
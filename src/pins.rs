@@ -0,0 +1,179 @@
+use crate::config::LIN_CONFIG_LOC;
+use crate::localmodinfo::{FileType, mod_file_finder};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+pub const PINS_FILE: &str = "pins";
+
+// A frozen copy of one workshop item's mods/<X> folder in the local mods directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pin {
+    pub mod_folder: String,
+    pub local_path: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Pins {
+    pub pins: BTreeMap<String, Pin>,
+}
+
+impl Pins {
+    pub fn get(&self, workshop_id: &str) -> Option<&Pin> {
+        self.pins.get(workshop_id)
+    }
+
+    pub fn is_pinned(&self, workshop_id: &str) -> bool {
+        self.pins.contains_key(workshop_id)
+    }
+
+    // workshop ID -> local copy, what the export reads instead of the workshop item
+    pub fn local_paths(&self) -> HashMap<String, String> {
+        self.pins
+            .iter()
+            .map(|(workshop_id, pin)| (workshop_id.clone(), pin.local_path.clone()))
+            .collect()
+    }
+
+    // workshop_id=mod folder\tlocal path
+    pub fn parse(content: &str) -> Pins {
+        let mut pins = Pins::default();
+        for line in content.lines() {
+            let Some((workshop_id, pin)) = line.split_once('=') else {
+                continue;
+            };
+            if let Some((mod_folder, local_path)) = pin.split_once('\t') {
+                pins.pins.insert(
+                    workshop_id.trim().to_string(),
+                    Pin {
+                        mod_folder: mod_folder.to_string(),
+                        local_path: local_path.to_string(),
+                    },
+                );
+            }
+        }
+        pins
+    }
+
+    pub fn render(&self) -> String {
+        self.pins
+            .iter()
+            .map(|(workshop_id, pin)| format!("{}={}\t{}\n", workshop_id, pin.mod_folder, pin.local_path))
+            .collect()
+    }
+}
+
+// The first <mod folder>_v<n> not already taken, older pins of the same mod stay untouched
+pub fn versioned_folder_name(mod_folder: &str, taken: impl Fn(&str) -> bool) -> String {
+    (1..)
+        .map(|version| format!("{}_v{}", mod_folder, version))
+        .find(|name| !taken(name))
+        .unwrap()
+}
+
+async fn copy_dir(source: &Path, destination: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(destination).await?;
+    let mut entries = fs::read_dir(source).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let target = destination.join(entry.file_name());
+        if entry.path().is_dir() {
+            Box::pin(copy_dir(&entry.path(), &target)).await?;
+        } else {
+            fs::copy(entry.path(), &target).await?;
+        }
+    }
+    Ok(())
+}
+
+// Copies the mod the export would use, the one holding the first mod.info found
+pub async fn pin_mod(
+    workshop_location: String,
+    workshop_id: String,
+    local_mods: PathBuf,
+) -> Result<(String, Pin), String> {
+    let mods = Path::new(&workshop_location).join(&workshop_id).join("mods");
+    let mod_info = mod_file_finder(mods.to_string_lossy().to_string(), FileType::ModInfo).await;
+    let Some(mod_folder) = Path::new(&mod_info)
+        .strip_prefix(&mods)
+        .ok()
+        .and_then(|relative| relative.components().next())
+        .map(|folder| folder.as_os_str().to_string_lossy().to_string())
+    else {
+        return Err(format!("No mod.info found under {}", mods.display()));
+    };
+
+    let name = versioned_folder_name(&mod_folder, |name| local_mods.join(name).exists());
+    let local_path = local_mods.join(name);
+    if let Err(err) = copy_dir(&mods.join(&mod_folder), &local_path).await {
+        let _ = fs::remove_dir_all(&local_path).await;
+        return Err(format!("Error copying {} -> Err: {}", mod_folder, err));
+    }
+    Ok((
+        workshop_id,
+        Pin {
+            mod_folder,
+            local_path: local_path.to_string_lossy().to_string(),
+        },
+    ))
+}
+
+// The copy has to go, the game would keep loading it over the workshop version.
+// Only an Ok result drops the pin, a failed delete keeps it around to retry
+pub async fn unpin_mod(workshop_id: String, pin: Pin) -> (String, Result<String, String>) {
+    let result = match fs::remove_dir_all(&pin.local_path).await {
+        Ok(_) => Ok(format!("Unpinned {}, the workshop version is used again", pin.mod_folder)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            Ok(format!("Unpinned {}, the local copy was already gone", pin.mod_folder))
+        }
+        Err(err) => Err(format!("Error removing {} -> Err: {}", pin.local_path, err)),
+    };
+    (workshop_id, result)
+}
+
+pub async fn load_pins() -> Pins {
+    match fs::read_to_string(LIN_CONFIG_LOC.to_owned() + PINS_FILE).await {
+        Ok(content) => Pins::parse(&content),
+        Err(_err) => Pins::default(),
+    }
+}
+
+// A second copy of an already pinned item would load next to the first one
+pub async fn discard_copy(pin: Pin) -> String {
+    match fs::remove_dir_all(&pin.local_path).await {
+        Ok(_) => format!("Already pinned, removed the extra copy {}", pin.local_path),
+        Err(err) => format!("Already pinned, error removing the extra copy {} -> Err: {}", pin.local_path, err),
+    }
+}
+
+// Hands back the status of what was changed, or the write error in its place
+pub async fn save_pins(pins: Pins, status: String) -> String {
+    match fs::write(LIN_CONFIG_LOC.to_owned() + PINS_FILE, pins.render()).await {
+        Ok(_) => status,
+        Err(err) => format!("Error writing pins -> Err: {}", err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pins_round_trip_and_pick_a_free_version() {
+        let pins = Pins {
+            pins: BTreeMap::from([(
+                "2169435993".to_string(),
+                Pin {
+                    mod_folder: "modoptions".to_string(),
+                    local_path: "/home/u/Zomboid/mods/modoptions_v2".to_string(),
+                },
+            )]),
+        };
+
+        assert_eq!(Pins::parse(&pins.render()), pins);
+        assert_eq!(
+            versioned_folder_name("modoptions", |name| name == "modoptions_v1"),
+            "modoptions_v2"
+        );
+        assert_eq!(versioned_folder_name("modoptions", |_name| false), "modoptions_v1");
+    }
+}
@@ -0,0 +1,195 @@
+use crate::AppMessage;
+use crate::config::LIN_CONFIG_LOC;
+use crate::custom_theme::{button, label_container_style, label_font, scrollable, text};
+use crate::form::{FieldKind, form_field};
+use iced::widget::{column, container, row};
+use iced::{Element, Length, Renderer};
+use iced_core::Theme;
+use tokio::fs;
+
+pub const PROFILES_FILE: &str = "profiles";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ServerProfile {
+    pub name: String,
+    pub workshop_roots: Vec<String>,
+    pub server_ini: String,
+    pub game_build: String,
+    pub preset: String,
+}
+
+impl ServerProfile {
+    // The first non-empty root is the one that gets scanned
+    pub fn workshop_root(&self) -> Option<String> {
+        self.workshop_roots
+            .iter()
+            .map(|root| root.trim())
+            .find(|root| !root.is_empty())
+            .map(|root| root.to_string())
+    }
+
+    pub fn field(&self, field: &str) -> String {
+        match field {
+            "name" => self.name.clone(),
+            "workshop" => self.workshop_roots.join(";"),
+            "server_ini" => self.server_ini.clone(),
+            "build" => self.game_build.clone(),
+            "preset" => self.preset.clone(),
+            _ => String::new(),
+        }
+    }
+
+    pub fn set_field(&mut self, field: &str, value: String) {
+        match field {
+            "name" => self.name = value,
+            "workshop" => self.workshop_roots = value.split(';').map(|root| root.to_string()).collect(),
+            "server_ini" => self.server_ini = value,
+            "build" => self.game_build = value,
+            "preset" => self.preset = value,
+            _ => {}
+        }
+    }
+}
+
+//=== Profiles are stored as ini style sections, one per server =====
+
+pub fn parse_profiles(content: &str) -> Vec<ServerProfile> {
+    let mut profiles: Vec<ServerProfile> = Vec::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            profiles.push(ServerProfile {
+                name: trimmed[1..trimmed.len() - 1].to_string(),
+                ..Default::default()
+            });
+        } else if let Some((key, value)) = trimmed.split_once('=')
+            && let Some(profile) = profiles.last_mut()
+        {
+            profile.set_field(key.trim(), value.trim().to_string());
+        }
+    }
+    profiles
+}
+
+pub fn render_profiles(profiles: &[ServerProfile]) -> String {
+    let mut output = String::new();
+
+    for profile in profiles {
+        output.push_str(&format!("[{}]\n", profile.name));
+        for field in ["workshop", "server_ini", "build", "preset"] {
+            output.push_str(&format!("{}={}\n", field, profile.field(field)));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+pub async fn load_profiles() -> Vec<ServerProfile> {
+    match fs::read_to_string(LIN_CONFIG_LOC.to_owned() + PROFILES_FILE).await {
+        Ok(content) => parse_profiles(&content),
+        Err(_err) => Vec::new(),
+    }
+}
+
+pub async fn save_profiles(profiles: Vec<ServerProfile>) -> String {
+    match fs::write(LIN_CONFIG_LOC.to_owned() + PROFILES_FILE, render_profiles(&profiles)).await {
+        Ok(_) => format!("Saved {} profiles", profiles.len()),
+        Err(err) => format!("Error writing profiles -> Err: {}", err),
+    }
+}
+
+const PROFILE_FIELDS: [(&str, &str, &str); 5] = [
+    ("name", "Name", "Shown on the start screen"),
+    ("workshop", "Workshop Roots", "Folders separated by ;, the first is scanned"),
+    ("server_ini", "Server ini", "e.g. ~/Zomboid/Server/servertest.ini"),
+    ("build", "Game Build", "e.g. 41 or 42"),
+    ("preset", "Active Preset", "Preset file name loaded when switching"),
+];
+
+#[derive(Debug, Default)]
+pub struct ProfileManager {
+    pub profiles: Vec<ServerProfile>,
+    pub editing: Option<usize>,
+    pub active: Option<usize>,
+    pub status: String,
+}
+
+impl ProfileManager {
+    fn profile_row(&self, index: usize) -> Element<'_, AppMessage, Theme, Renderer> {
+        let profile = &self.profiles[index];
+        let marker = match self.active == Some(index) {
+            true => " (active)",
+            false => "",
+        };
+        row![
+            text(format!("{}{}", profile.name, marker)).width(Length::Fixed(260.0)),
+            button(text("Use")).on_press(AppMessage::SelectProfile(index)),
+            button(text("Edit")).on_press(AppMessage::EditProfile(index)),
+            button(text("Remove")).on_press(AppMessage::RemoveProfile(index))
+        ]
+        .spacing(10)
+        .padding(2)
+        .into()
+    }
+
+    fn profile_form(&self, index: usize) -> Element<'_, AppMessage, Theme, Renderer> {
+        let mut form = column![];
+        for (field, label, note) in PROFILE_FIELDS {
+            let value = self.profiles[index].field(field);
+            form = form.push(form_field(
+                label,
+                FieldKind::Text,
+                &value,
+                "",
+                note,
+                move |value| AppMessage::ProfileField(field.to_string(), value),
+            ));
+        }
+        form.into()
+    }
+
+    pub fn profiles_view(&self) -> iced::widget::Container<'_, AppMessage> {
+        let mut list = column![];
+        for index in 0..self.profiles.len() {
+            list = list.push(self.profile_row(index));
+        }
+        if let Some(index) = self.editing {
+            list = list.push(self.profile_form(index));
+        }
+
+        container(column![
+            row![
+                container(text("Server Profiles").font(label_font()))
+                    .padding(8)
+                    .style(label_container_style)
+            ],
+            scrollable(list.padding(5)).height(Length::Fill),
+            row![
+                button(text("New Profile")).on_press(AppMessage::NewProfile),
+                button(text("Save Profiles")).on_press(AppMessage::SaveProfiles),
+                button(text("Return Home")).on_press(AppMessage::UpdateView(crate::State::InitialMain)),
+                text(&self.status)
+            ]
+            .spacing(10)
+            .padding(5)
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_round_trip() {
+        let profiles = vec![ServerProfile {
+            name: "Main".to_string(),
+            workshop_roots: vec!["/a/108600".to_string(), "/b/108600".to_string()],
+            server_ini: "/home/u/Zomboid/Server/main.ini".to_string(),
+            game_build: "41".to_string(),
+            preset: "event".to_string(),
+        }];
+        assert_eq!(parse_profiles(&render_profiles(&profiles)), profiles);
+    }
+}
@@ -0,0 +1,417 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+use crate::localmodinfo::collect_files;
+use crate::text_encoding::decode_text;
+
+//=== Tables written by the game itself, never reported as leftovers =====
+pub const VANILLA_TABLES: [&str; 5] = [
+    "ZombieLore",
+    "ZombieConfig",
+    "MultiplierConfig",
+    "Map",
+    "Basement",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OptionType {
+    Boolean,
+    Integer,
+    Double,
+    Enum,
+    Text,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SandboxOption {
+    pub namespace: Option<String>,
+    pub name: String,
+    pub option_type: OptionType,
+    pub default: String,
+    pub min: Option<String>,
+    pub max: Option<String>,
+}
+
+impl SandboxOption {
+    pub fn path(&self) -> Vec<String> {
+        match &self.namespace {
+            Some(namespace) => vec![namespace.clone(), self.name.clone()],
+            None => vec![self.name.clone()],
+        }
+    }
+
+    // Value as it should appear on the right hand side of a SandboxVars.lua line
+    pub fn lua_default(&self) -> String {
+        match self.option_type {
+            OptionType::Text => format!("\"{}\"", self.default.trim_matches('"')),
+            OptionType::Boolean => self.default.to_lowercase(),
+            OptionType::Double if !self.default.contains('.') => format!("{}.0", self.default),
+            _ => self.default.clone(),
+        }
+    }
+}
+
+//=== Parsing of media/sandbox-options.txt =====
+
+// Options with a type the game doesn't know come back by name next to the parsed ones
+pub fn parse_sandbox_options(content: &str) -> (Vec<SandboxOption>, Vec<String>) {
+    let mut options: Vec<SandboxOption> = Vec::new();
+    let mut skipped: Vec<String> = Vec::new();
+    let mut rest = content;
+
+    while let Some(start) = rest.find("option ") {
+        rest = &rest[start + "option ".len()..];
+
+        let Some(open) = rest.find('{') else { break };
+        let Some(close) = rest[open..].find('}') else { break };
+
+        let full_name = rest[..open].trim().to_string();
+        let body = &rest[open + 1..open + close];
+        rest = &rest[open + close + 1..];
+
+        let (namespace, name) = match full_name.split_once('.') {
+            Some((namespace, name)) => (Some(namespace.to_string()), name.to_string()),
+            None => (None, full_name),
+        };
+
+        let mut fields: BTreeMap<String, String> = BTreeMap::new();
+        for field in body.split([',', '\n']) {
+            if let Some((key, value)) = field.split_once('=') {
+                fields.insert(key.trim().to_lowercase(), value.trim().to_string());
+            }
+        }
+
+        let option_type = match fields.get("type").map(|kind| kind.to_lowercase()).as_deref() {
+            Some("boolean") => OptionType::Boolean,
+            Some("integer") => OptionType::Integer,
+            Some("double") => OptionType::Double,
+            Some("enum") => OptionType::Enum,
+            Some("string") => OptionType::Text,
+            _ => {
+                skipped.push(match &namespace {
+                    Some(namespace) => format!("{}.{}", namespace, name),
+                    None => name,
+                });
+                continue;
+            }
+        };
+
+        options.push(SandboxOption {
+            namespace,
+            name,
+            option_type,
+            default: fields.get("default").cloned().unwrap_or_default(),
+            min: fields.get("min").cloned(),
+            max: fields.get("max").cloned(),
+        });
+    }
+    (options, skipped)
+}
+
+//=== Line preserving model of <server>_SandboxVars.lua =====
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LuaEntry {
+    pub path: Vec<String>,
+    pub value: String,
+    line: usize,
+    value_range: (usize, usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct LuaTableSpan {
+    path: Vec<String>,
+    open: usize,
+    close: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SandboxDocument {
+    lines: Vec<String>,
+    line_ending: String,
+    pub entries: Vec<LuaEntry>,
+    tables: Vec<LuaTableSpan>,
+}
+
+// Position of a `--` comment that isn't inside a string literal
+fn comment_start(line: &str) -> Option<usize> {
+    let mut in_string = false;
+    let bytes = line.as_bytes();
+    for index in 0..bytes.len() {
+        match bytes[index] {
+            b'"' => in_string = !in_string,
+            b'-' if !in_string && bytes.get(index + 1) == Some(&b'-') => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+impl SandboxDocument {
+    pub fn parse(content: &str) -> SandboxDocument {
+        let line_ending = match content.contains("\r\n") {
+            true => "\r\n",
+            false => "\n",
+        };
+        let mut document = SandboxDocument {
+            lines: content.lines().map(|line| line.to_string()).collect(),
+            line_ending: line_ending.to_string(),
+            ..Default::default()
+        };
+        document.index();
+        document
+    }
+
+    pub fn empty() -> SandboxDocument {
+        SandboxDocument::parse("SandboxVars = {\n    VERSION = 5,\n}\n")
+    }
+
+    fn index(&mut self) {
+        let mut stack: Vec<(Vec<String>, usize)> = Vec::new();
+        self.entries.clear();
+        self.tables.clear();
+
+        for (line_number, line) in self.lines.iter().enumerate() {
+            let code = match comment_start(line) {
+                Some(position) => &line[..position],
+                None => line.as_str(),
+            };
+            let trimmed = code.trim();
+
+            if trimmed.starts_with('}') {
+                if let Some((path, open)) = stack.pop() {
+                    self.tables.push(LuaTableSpan {
+                        path,
+                        open,
+                        close: line_number,
+                    });
+                }
+                continue;
+            }
+
+            let Some((key, value)) = trimmed.split_once('=') else {
+                continue;
+            };
+            let key = key.trim().to_string();
+            let value = value.trim().trim_end_matches(',').trim();
+
+            if value == "{" {
+                // The outer `SandboxVars = {` is the root and isn't part of any path
+                let path = match stack.last() {
+                    Some((parent, _)) => [parent.clone(), vec![key]].concat(),
+                    None => Vec::new(),
+                };
+                stack.push((path, line_number));
+            } else if let Some((parent, _)) = stack.last() {
+                let start = code.find('=').unwrap() + 1;
+                let start = start + (code[start..].len() - code[start..].trim_start().len());
+                self.entries.push(LuaEntry {
+                    path: [parent.clone(), vec![key]].concat(),
+                    value: value.to_string(),
+                    line: line_number,
+                    value_range: (start, start + value.len()),
+                });
+            }
+        }
+    }
+
+    pub fn get(&self, path: &[String]) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|entry| entry.path == path)
+            .map(|entry| entry.value.as_str())
+    }
+
+    pub fn has_table(&self, path: &[String]) -> bool {
+        self.tables.iter().any(|table| table.path == path)
+    }
+
+    // Replaces only the value portion of an existing line, or inserts a new one
+    pub fn set(&mut self, path: &[String], value: &str) {
+        match self.entries.iter().find(|entry| entry.path == path) {
+            Some(entry) => {
+                let (start, end) = entry.value_range;
+                self.lines[entry.line].replace_range(start..end, value);
+            }
+            None => {
+                self.ensure_table(&path[..path.len() - 1]);
+                let table = self
+                    .tables
+                    .iter()
+                    .find(|table| table.path[..] == path[..path.len() - 1])
+                    .unwrap();
+                let indent = "    ".repeat(path.len());
+                let line = format!("{}{} = {},", indent, path[path.len() - 1], value);
+                self.lines.insert(table.close, line);
+            }
+        }
+        self.index();
+    }
+
+    fn ensure_table(&mut self, path: &[String]) {
+        if self.has_table(path) {
+            return;
+        }
+        if path.is_empty() {
+            self.lines.push("SandboxVars = {".to_string());
+            self.lines.push("}".to_string());
+            self.index();
+            return;
+        }
+        self.ensure_table(&path[..path.len() - 1]);
+
+        let parent = self
+            .tables
+            .iter()
+            .find(|table| table.path[..] == path[..path.len() - 1])
+            .unwrap();
+        let indent = "    ".repeat(path.len());
+        let close = parent.close;
+        self.lines
+            .insert(close, format!("{}{} = {{", indent, path[path.len() - 1]));
+        self.lines.insert(close + 1, format!("{}}},", indent));
+        self.index();
+    }
+
+    pub fn namespaces(&self) -> Vec<String> {
+        self.tables
+            .iter()
+            .filter(|table| table.path.len() == 1)
+            .map(|table| table.path[0].clone())
+            .collect()
+    }
+
+    pub fn render(&self) -> String {
+        let mut output = self.lines.join(&self.line_ending);
+        output.push_str(&self.line_ending);
+        output
+    }
+}
+
+//=== Merging mod defaults into the server file =====
+
+#[derive(Debug, Clone, Default)]
+pub struct SandboxReport {
+    pub target: String,
+    pub added: Vec<String>,
+    pub kept: usize,
+    pub leftover: Vec<String>,
+    pub skipped: Vec<String>,
+    pub unchanged: bool,
+    pub errors: Vec<String>,
+}
+
+pub fn merge_options(document: &mut SandboxDocument, options: &[SandboxOption]) -> SandboxReport {
+    let mut report = SandboxReport::default();
+    let mut known: HashSet<Vec<String>> = HashSet::new();
+
+    for option in options {
+        let path = option.path();
+        match document.get(&path) {
+            Some(_) => report.kept += 1,
+            None => {
+                document.set(&path, &option.lua_default());
+                report.added.push(path.join("."));
+            }
+        }
+        known.insert(path);
+    }
+
+    for entry in &document.entries {
+        let from_mod = entry.path.len() > 1 && !VANILLA_TABLES.contains(&entry.path[0].as_str());
+        if from_mod && !known.contains(&entry.path) {
+            report.leftover.push(entry.path.join("."));
+        }
+    }
+    report
+}
+
+pub async fn collect_sandbox_options(
+    workshop_location: String,
+    workshop_ids: Vec<String>,
+) -> (Vec<SandboxOption>, Vec<String>) {
+    let mut options: Vec<SandboxOption> = Vec::new();
+    let mut skipped: Vec<String> = Vec::new();
+
+    for id in workshop_ids {
+        let mut files: Vec<PathBuf> = Vec::new();
+        let mod_directory = format!("{}/{}/mods/", workshop_location, id);
+        let _ = collect_files(Path::new(&mod_directory), &mut files).await;
+
+        for file in files {
+            if file.file_name().is_some_and(|name| name == "sandbox-options.txt")
+                && let Ok(bytes) = fs::read(&file).await
+            {
+                let (parsed, unknown) = parse_sandbox_options(&decode_text(&bytes).0);
+                for option in parsed {
+                    if !options.iter().any(|known| known.path() == option.path()) {
+                        options.push(option);
+                    }
+                }
+                skipped.extend(unknown.into_iter().map(|name| format!("{} ({})", name, id)));
+            }
+        }
+    }
+    (options, skipped)
+}
+
+pub async fn generate_sandbox_defaults(
+    workshop_location: String,
+    workshop_ids: Vec<String>,
+    sandbox_path: String,
+) -> SandboxReport {
+    let (options, skipped) = collect_sandbox_options(workshop_location, workshop_ids).await;
+
+    let original = fs::read_to_string(&sandbox_path).await.ok();
+    let mut document = match &original {
+        Some(content) => SandboxDocument::parse(content),
+        None => SandboxDocument::empty(),
+    };
+
+    let mut report = merge_options(&mut document, &options);
+    report.target = sandbox_path.clone();
+    report.skipped = skipped;
+
+    let rendered = document.render();
+    report.unchanged = original.as_deref() == Some(rendered.as_str());
+    if !report.unchanged
+        && let Err(err) = fs::write(&sandbox_path, rendered).await
+    {
+        report.errors.push(format!("Error writing {} -> Err: {}", sandbox_path, err));
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPTIONS: &str = "VERSION = 1,\n\noption MyMod.Speed\n{\n    type = integer, min = 0, max = 10, default = 3,\n    page = MyMod, translation = MyMod_Speed,\n}\n\noption MyMod.Enabled\n{\n    type = boolean, default = true,\n}\n";
+
+    const SANDBOX: &str = "SandboxVars = {\n    VERSION = 5,\n    -- How many zombies\n    Zombies = 4,\n    MyMod = {\n        Speed = 7,\n    },\n    OldMod = {\n        Thing = 1,\n    },\n}\n";
+
+    #[test]
+    fn parses_sandbox_options() {
+        let (options, skipped) =
+            parse_sandbox_options(&format!("{}option MyMod.Colour\n{{\n    type = color,\n}}\n", OPTIONS));
+        assert_eq!(skipped, vec!["MyMod.Colour"]);
+        assert_eq!(options.len(), 2);
+        assert_eq!(options[0].path(), vec!["MyMod", "Speed"]);
+        assert_eq!(options[0].option_type, OptionType::Integer);
+        assert_eq!(options[0].max.as_deref(), Some("10"));
+        assert_eq!(options[1].lua_default(), "true");
+    }
+
+    #[test]
+    fn merge_keeps_values_and_reports_leftovers() {
+        let mut document = SandboxDocument::parse(SANDBOX);
+        let report = merge_options(&mut document, &parse_sandbox_options(OPTIONS).0);
+
+        assert_eq!(report.kept, 1);
+        assert_eq!(report.added, vec!["MyMod.Enabled"]);
+        assert_eq!(report.leftover, vec!["OldMod.Thing"]);
+        assert!(document.render().contains("        Speed = 7,\n        Enabled = true,\n    },"));
+        assert!(document.render().contains("    -- How many zombies\n"));
+    }
+}
@@ -0,0 +1,263 @@
+use crate::AppMessage;
+use crate::custom_theme::{button, label_container_style, label_font, scrollable, text};
+use crate::form::{FieldKind, form_field, same_value, validate};
+use crate::sandbox::SandboxDocument;
+use iced::widget::{column, container, row};
+use iced::{Element, Length, Renderer};
+use iced_core::Theme;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy)]
+pub struct VanillaOption {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub category: &'static str,
+    pub kind: FieldKind,
+    pub default: &'static str,
+}
+
+// Enum options are stored as 1-based indices into these labels
+const POPULATION: &[&str] = &["Insane", "Very High", "High", "Normal", "Low", "None"];
+const DISTRIBUTION: &[&str] = &["Urban Focused", "Uniform"];
+const DAY_LENGTH: &[&str] = &[
+    "15 Minutes", "30 Minutes", "1 Hour", "2 Hours", "3 Hours", "4 Hours", "5 Hours", "6 Hours",
+    "7 Hours", "8 Hours", "9 Hours", "10 Hours", "11 Hours", "12 Hours", "13 Hours", "14 Hours",
+    "15 Hours", "16 Hours", "17 Hours", "18 Hours", "19 Hours", "20 Hours", "21 Hours",
+    "22 Hours", "23 Hours", "Real-time",
+];
+const START_TIME: &[&str] = &["7 AM", "9 AM", "12 PM", "2 PM", "5 PM", "9 PM", "12 AM", "2 AM", "5 AM"];
+const SHUTOFF: &[&str] = &[
+    "Instant", "0-30 Days", "0-2 Months", "0-6 Months", "0-1 Year", "0-5 Years", "2-6 Months",
+    "6-12 Months",
+];
+const RARITY: &[&str] = &["Extremely Rare", "Rare", "Normal", "Common", "Abundant"];
+const LOOT_RESPAWN: &[&str] = &["None", "Every Day", "Every Week", "Every Month", "Every Two Months"];
+const TEMPERATURE: &[&str] = &["Very Cold", "Cold", "Normal", "Hot", "Very Hot"];
+const RAIN: &[&str] = &["Very Dry", "Dry", "Normal", "Rainy", "Very Rainy"];
+const SPEED: &[&str] = &["Very Fast", "Fast", "Normal", "Slow", "Very Slow"];
+const ABUNDANCE: &[&str] = &["Very Poor", "Poor", "Normal", "Abundant", "Very Abundant"];
+const FREQUENCY: &[&str] = &["Never", "Extremely Rare", "Rare", "Sometimes", "Often", "Very Often"];
+const ZOMBIE_SPEED: &[&str] = &["Sprinters", "Fast Shamblers", "Shamblers", "Random"];
+const STRENGTH: &[&str] = &["Superhuman", "Normal", "Weak", "Random"];
+const TOUGHNESS: &[&str] = &["Tough", "Normal", "Fragile", "Random"];
+const TRANSMISSION: &[&str] = &["Blood + Saliva", "Saliva Only", "Everyone's Infected", "None"];
+const MORTALITY: &[&str] = &[
+    "Instant", "0-30 Seconds", "0-1 Minutes", "0-12 Hours", "2-3 Days", "1-2 Weeks", "Never",
+];
+const REANIMATE: &[&str] = &["Instant", "0-30 Seconds", "0-1 Minutes", "0-12 Hours", "2-3 Days", "1-2 Weeks"];
+const COGNITION: &[&str] = &["Navigate + Use Doors", "Navigate", "Basic Navigation", "Random"];
+const MEMORY: &[&str] = &["Long", "Normal", "Short", "None", "Random"];
+const SENSES: &[&str] = &["Eagle / Pinpoint", "Normal", "Poor", "Random"];
+const ACTIVE: &[&str] = &["Both", "Night", "Day"];
+
+const fn option(
+    key: &'static str,
+    label: &'static str,
+    category: &'static str,
+    kind: FieldKind,
+    default: &'static str,
+) -> VanillaOption {
+    VanillaOption { key, label, category, kind, default }
+}
+
+pub const VANILLA_OPTIONS: &[VanillaOption] = &[
+    option("Zombies", "Zombie Count", "Population", FieldKind::Enum(POPULATION), "4"),
+    option("Distribution", "Distribution", "Population", FieldKind::Enum(DISTRIBUTION), "1"),
+    option("ZombieConfig.PopulationMultiplier", "Population Multiplier", "Population", FieldKind::Double(0.0, 4.0), "1.0"),
+    option("ZombieConfig.PopulationStartMultiplier", "Start Multiplier", "Population", FieldKind::Double(0.0, 4.0), "1.0"),
+    option("ZombieConfig.PopulationPeakMultiplier", "Peak Multiplier", "Population", FieldKind::Double(0.0, 4.0), "1.5"),
+    option("ZombieConfig.PopulationPeakDay", "Peak Day", "Population", FieldKind::Integer(1, 365), "28"),
+    option("ZombieConfig.RespawnHours", "Respawn Hours", "Population", FieldKind::Double(0.0, 8760.0), "72.0"),
+    option("ZombieConfig.RespawnUnseenHours", "Respawn Unseen Hours", "Population", FieldKind::Double(0.0, 8760.0), "16.0"),
+    option("ZombieConfig.RespawnMultiplier", "Respawn Multiplier", "Population", FieldKind::Double(0.0, 1.0), "0.1"),
+    option("ZombieConfig.RedistributeHours", "Redistribute Hours", "Population", FieldKind::Double(0.0, 8760.0), "12.0"),
+    option("ZombieConfig.FollowSoundDistance", "Follow Sound Distance", "Population", FieldKind::Integer(10, 1000), "100"),
+    option("ZombieConfig.RallyGroupSize", "Rally Group Size", "Population", FieldKind::Integer(0, 1000), "20"),
+    option("ZombieLore.Speed", "Speed", "Zombie Lore", FieldKind::Enum(ZOMBIE_SPEED), "2"),
+    option("ZombieLore.Strength", "Strength", "Zombie Lore", FieldKind::Enum(STRENGTH), "2"),
+    option("ZombieLore.Toughness", "Toughness", "Zombie Lore", FieldKind::Enum(TOUGHNESS), "2"),
+    option("ZombieLore.Transmission", "Transmission", "Zombie Lore", FieldKind::Enum(TRANSMISSION), "1"),
+    option("ZombieLore.Mortality", "Infection Mortality", "Zombie Lore", FieldKind::Enum(MORTALITY), "5"),
+    option("ZombieLore.Reanimate", "Reanimation Time", "Zombie Lore", FieldKind::Enum(REANIMATE), "3"),
+    option("ZombieLore.Cognition", "Cognition", "Zombie Lore", FieldKind::Enum(COGNITION), "3"),
+    option("ZombieLore.Memory", "Memory", "Zombie Lore", FieldKind::Enum(MEMORY), "2"),
+    option("ZombieLore.Sight", "Sight", "Zombie Lore", FieldKind::Enum(SENSES), "2"),
+    option("ZombieLore.Hearing", "Hearing", "Zombie Lore", FieldKind::Enum(SENSES), "2"),
+    option("ZombieLore.ActiveOnly", "Active", "Zombie Lore", FieldKind::Enum(ACTIVE), "1"),
+    option("ZombieLore.ThumpNoChasing", "Thump Without Chasing", "Zombie Lore", FieldKind::Boolean, "false"),
+    option("ZombieLore.ThumpOnConstruction", "Thump On Construction", "Zombie Lore", FieldKind::Boolean, "true"),
+    option("ZombieLore.TriggerHouseAlarm", "Trigger House Alarms", "Zombie Lore", FieldKind::Boolean, "false"),
+    option("ZombieLore.ZombiesDragDown", "Drag Down", "Zombie Lore", FieldKind::Boolean, "true"),
+    option("ZombieLore.ZombiesFenceLunge", "Fence Lunge", "Zombie Lore", FieldKind::Boolean, "true"),
+    option("DayLength", "Day Length", "Time", FieldKind::Enum(DAY_LENGTH), "3"),
+    option("StartMonth", "Start Month", "Time", FieldKind::Integer(1, 12), "7"),
+    option("StartDay", "Start Day", "Time", FieldKind::Integer(1, 31), "9"),
+    option("StartTime", "Start Time", "Time", FieldKind::Enum(START_TIME), "2"),
+    option("WaterShut", "Water Shutoff", "Time", FieldKind::Enum(SHUTOFF), "2"),
+    option("ElecShut", "Electricity Shutoff", "Time", FieldKind::Enum(SHUTOFF), "2"),
+    option("WaterShutModifier", "Water Shutoff Day", "Time", FieldKind::Integer(-1, 2147483647), "14"),
+    option("ElecShutModifier", "Electricity Shutoff Day", "Time", FieldKind::Integer(-1, 2147483647), "14"),
+    option("FoodLoot", "Food Loot", "Loot", FieldKind::Enum(RARITY), "3"),
+    option("CannedFoodLoot", "Canned Food Loot", "Loot", FieldKind::Enum(RARITY), "3"),
+    option("WeaponLoot", "Weapon Loot", "Loot", FieldKind::Enum(RARITY), "3"),
+    option("RangedWeaponLoot", "Ranged Weapon Loot", "Loot", FieldKind::Enum(RARITY), "3"),
+    option("AmmoLoot", "Ammo Loot", "Loot", FieldKind::Enum(RARITY), "3"),
+    option("MedicalLoot", "Medical Loot", "Loot", FieldKind::Enum(RARITY), "3"),
+    option("LiteratureLoot", "Literature Loot", "Loot", FieldKind::Enum(RARITY), "3"),
+    option("OtherLoot", "Other Loot", "Loot", FieldKind::Enum(RARITY), "3"),
+    option("LootRespawn", "Loot Respawn", "Loot", FieldKind::Enum(LOOT_RESPAWN), "1"),
+    option("Temperature", "Temperature", "World", FieldKind::Enum(TEMPERATURE), "3"),
+    option("Rain", "Rain", "World", FieldKind::Enum(RAIN), "3"),
+    option("ErosionSpeed", "Erosion Speed", "World", FieldKind::Enum(SPEED), "3"),
+    option("ErosionDays", "Erosion Days", "World", FieldKind::Integer(-1, 36500), "0"),
+    option("Farming", "Farming Speed", "World", FieldKind::Enum(SPEED), "3"),
+    option("NatureAbundance", "Nature Abundance", "World", FieldKind::Enum(ABUNDANCE), "3"),
+    option("Alarm", "House Alarm Frequency", "World", FieldKind::Enum(FREQUENCY), "4"),
+    option("FireSpread", "Fire Spread", "World", FieldKind::Boolean, "true"),
+    option("AllowExteriorGenerator", "Exterior Generators", "World", FieldKind::Boolean, "true"),
+    option("XpMultiplier", "XP Multiplier", "Character", FieldKind::Double(0.001, 1000.0), "1.0"),
+    option("StarterKit", "Starter Kit", "Character", FieldKind::Boolean, "false"),
+    option("Nutrition", "Nutrition", "Character", FieldKind::Boolean, "true"),
+    option("BoneFracture", "Bone Fractures", "Character", FieldKind::Boolean, "true"),
+    option("MultiHitZombies", "Multi Hit Zombies", "Character", FieldKind::Boolean, "false"),
+];
+
+pub const CATEGORIES: [&str; 6] = ["Population", "Zombie Lore", "Time", "Loot", "World", "Character"];
+
+fn key_path(key: &str) -> Vec<String> {
+    key.split('.').map(|part| part.to_string()).collect()
+}
+
+#[derive(Debug, Default)]
+pub struct SandboxEditor {
+    pub path: String,
+    pub document: SandboxDocument,
+    pub inputs: HashMap<String, String>,
+    pub category: String,
+    pub status: String,
+}
+
+impl SandboxEditor {
+    pub fn load(&mut self, path: String, content: Option<String>) {
+        self.document = match content {
+            Some(content) => SandboxDocument::parse(&content),
+            None => SandboxDocument::empty(),
+        };
+        self.inputs.clear();
+        for option in VANILLA_OPTIONS {
+            let value = self
+                .document
+                .get(&key_path(option.key))
+                .unwrap_or(option.default)
+                .to_string();
+            self.inputs.insert(option.key.to_string(), value);
+        }
+        self.path = path;
+        self.category = CATEGORIES[0].to_string();
+        self.status = String::new();
+    }
+
+    pub fn invalid_fields(&self) -> Vec<String> {
+        VANILLA_OPTIONS
+            .iter()
+            .filter(|option| validate(option.kind, &self.inputs[option.key]).is_err())
+            .map(|option| option.label.to_string())
+            .collect()
+    }
+
+    // Only lines whose value actually changed are touched
+    pub fn apply(&mut self) -> usize {
+        let mut changed = 0;
+        for option in VANILLA_OPTIONS {
+            let path = key_path(option.key);
+            let Ok(value) = validate(option.kind, &self.inputs[option.key]) else {
+                continue;
+            };
+            let current = self.document.get(&path).unwrap_or(option.default);
+            if !same_value(option.kind, current, &value) {
+                self.document.set(&path, &value);
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    fn field(&self, option: &VanillaOption) -> Element<'_, AppMessage, Theme, Renderer> {
+        let key = option.key.to_string();
+        form_field(
+            option.label,
+            option.kind,
+            &self.inputs[option.key],
+            option.default,
+            "",
+            move |value| AppMessage::SandboxField(key.clone(), value),
+        )
+    }
+
+    pub fn sandbox_editor_view(&self) -> iced::widget::Container<'_, AppMessage> {
+        let mut tabs = row![];
+        for category in CATEGORIES {
+            tabs = tabs.push(
+                button(text(category))
+                    .padding(4)
+                    .on_press(AppMessage::SandboxCategory(category.to_string())),
+            );
+        }
+
+        let mut fields = column![];
+        for option in VANILLA_OPTIONS.iter().filter(|option| option.category == self.category) {
+            fields = fields.push(self.field(option));
+        }
+
+        let untouched = self.document.entries.len().saturating_sub(
+            VANILLA_OPTIONS
+                .iter()
+                .filter(|option| self.document.get(&key_path(option.key)).is_some())
+                .count(),
+        );
+
+        container(column![
+            row![
+                container(text(format!("Sandbox settings: {}", self.path)).font(label_font()))
+                    .padding(8)
+                    .style(label_container_style)
+            ],
+            tabs.spacing(4).padding(5),
+            scrollable(fields.padding(5)).height(Length::Fill),
+            text(format!("{} other entries (mods, unknown keys) are left untouched", untouched)),
+            row![
+                button(text("Save")).on_press(AppMessage::SandboxSave),
+                button(text("Return Home")).on_press(AppMessage::UpdateView(crate::State::InitialMain)),
+                text(&self.status)
+            ]
+            .spacing(10)
+            .padding(5)
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_only_rewrites_changed_lines() {
+        let source = "SandboxVars = {\n    Zombies = 4,\n    -- keep me\n    Custom = \"x\",\n    ZombieLore = {\n        Speed = 2,\n    },\n}\n";
+        let mut editor = SandboxEditor::default();
+        editor.load("test.lua".to_string(), Some(source.to_string()));
+        editor.inputs.insert("ZombieLore.Speed".to_string(), "1".to_string());
+
+        assert_eq!(editor.apply(), 1);
+        assert_eq!(editor.document.render(), source.replace("Speed = 2", "Speed = 1"));
+    }
+
+    #[test]
+    fn untouched_doubles_round_trip_byte_for_byte() {
+        let source = "SandboxVars = {\n    ZombieConfig = {\n        RespawnMultiplier = 0.10,\n        PopulationPeakMultiplier = 1.50,\n        RespawnHours = 72,\n    },\n}\n";
+        let mut editor = SandboxEditor::default();
+        editor.load("test.lua".to_string(), Some(source.to_string()));
+
+        assert_eq!(editor.apply(), 0);
+        assert_eq!(editor.document.render(), source);
+    }
+}
@@ -0,0 +1,258 @@
+use crate::conflicts::mod_sources;
+use crate::custom_theme::{button, label_container_style, label_font, scrollable, text, text_input};
+use crate::localmodinfo::collect_files;
+use crate::text_encoding::decode_text;
+use crate::{AppMessage, State};
+use iced::widget::{column, container, row};
+use iced::Length;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+// Showing every definition of a big modpack at once makes the list unusable
+const SEARCH_RESULT_LIMIT: usize = 200;
+
+// One `kind Name { ... }` block inside a `module X { ... }` block
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptDefinition {
+    pub kind: String,
+    pub module: String,
+    pub name: String,
+    pub source: String,
+    pub file: String,
+}
+
+impl ScriptDefinition {
+    pub fn id(&self) -> String {
+        format!("{}.{}", self.module, self.name)
+    }
+}
+
+fn strip_comments(content: &str) -> String {
+    let mut output = String::new();
+    let mut chars = content.chars().peekable();
+    while let Some(character) = chars.next() {
+        match (character, chars.peek()) {
+            ('/', Some('*')) => {
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+                output.push(' ');
+            }
+            ('/', Some('/')) => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        output.push('\n');
+                        break;
+                    }
+                }
+            }
+            _ => output.push(character),
+        }
+    }
+    output
+}
+
+// Only the headers of blocks directly inside a module are definitions, nested
+// blocks such as recipe sources or vehicle parts belong to their parent
+pub fn parse_scripts(content: &str, source: &str, file: &str) -> Vec<ScriptDefinition> {
+    let mut definitions: Vec<ScriptDefinition> = Vec::new();
+    let mut module: Option<String> = None;
+    let mut depth: usize = 0;
+    let mut header = String::new();
+
+    for character in strip_comments(content).chars() {
+        match character {
+            '{' => {
+                let words: Vec<&str> = header.split_whitespace().collect();
+                match (depth, words.as_slice(), &module) {
+                    (0, ["module", name, ..], _) => module = Some(name.to_string()),
+                    (1, [kind, name @ ..], Some(module)) if !name.is_empty() && *kind != "imports" => {
+                        definitions.push(ScriptDefinition {
+                            kind: kind.to_string(),
+                            module: module.clone(),
+                            name: name.join(" "),
+                            source: source.to_string(),
+                            file: file.to_string(),
+                        })
+                    }
+                    _ => {}
+                }
+                depth += 1;
+                header.clear();
+            }
+            '}' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    module = None;
+                }
+                header.clear();
+            }
+            // Properties end in commas, only the text after the last one can be a header
+            ',' => header.clear(),
+            _ => header.push(character),
+        }
+    }
+    definitions
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptCollision {
+    pub kind: String,
+    pub id: String,
+    // Each mod defining the ID in load order, the last definition is the one the game keeps
+    pub sources: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ScriptIndex {
+    pub definitions: Vec<ScriptDefinition>,
+    pub errors: Vec<String>,
+    pub search: String,
+}
+
+impl ScriptIndex {
+    pub fn search_results(&self) -> Vec<&ScriptDefinition> {
+        let query = self.search.to_lowercase();
+        self.definitions
+            .iter()
+            .filter(|definition| {
+                query.is_empty()
+                    || definition.id().to_lowercase().contains(&query)
+                    || definition.kind.to_lowercase().contains(&query)
+            })
+            .take(SEARCH_RESULT_LIMIT)
+            .collect()
+    }
+
+    // The same kind and Module.Name defined by more than one mod
+    pub fn collisions(&self) -> Vec<ScriptCollision> {
+        let mut by_id: BTreeMap<(String, String), Vec<String>> = BTreeMap::new();
+        for definition in &self.definitions {
+            let sources = by_id
+                .entry((definition.kind.clone(), definition.id()))
+                .or_default();
+            if !sources.contains(&definition.source) {
+                sources.push(definition.source.clone());
+            }
+        }
+        by_id
+            .into_iter()
+            .filter(|(_key, sources)| sources.len() > 1)
+            .map(|((kind, id), sources)| ScriptCollision { kind, id, sources })
+            .collect()
+    }
+
+    pub fn script_index_view(&self) -> iced::widget::Container<'_, AppMessage> {
+        let collisions = self.collisions();
+        let mut collision_col =
+            column![text(format!("IDs defined by more than one mod: {}", collisions.len()))].spacing(6);
+        for collision in collisions {
+            collision_col = collision_col.push(column![
+                text(format!("{} {}", collision.kind, collision.id)).font(label_font()),
+                text(format!("  {}", collision.sources.join(" -> ")))
+            ]);
+        }
+        for error in &self.errors {
+            collision_col = collision_col.push(text(error));
+        }
+
+        let results = self.search_results();
+        let mut result_col = column![text(format!(
+            "Showing {} of {} definitions",
+            results.len(),
+            self.definitions.len()
+        ))];
+        for definition in results {
+            result_col = result_col.push(text(format!(
+                "{} {}  [{}, {}]",
+                definition.kind,
+                definition.id(),
+                definition.source,
+                definition.file
+            )));
+        }
+
+        container(column![
+            row![
+                container(text("Script Definitions").font(label_font()))
+                    .padding(8)
+                    .style(label_container_style)
+            ],
+            text_input("Search Module.Item or kind, e.g. Base.Axe or recipe", &self.search)
+                .on_input(AppMessage::ScriptSearch),
+            row![
+                scrollable(result_col).width(Length::FillPortion(1)),
+                scrollable(collision_col).width(Length::FillPortion(1))
+            ]
+            .height(Length::Fill)
+            .padding(5),
+            row![button(text("Back to Mods")).on_press(AppMessage::UpdateView(State::LoadedMain))]
+        ])
+    }
+}
+
+// workshop_ids in load order so collisions list the winning mod last
+pub async fn index_scripts(workshop_location: String, workshop_ids: Vec<String>) -> ScriptIndex {
+    let mut index = ScriptIndex::default();
+
+    for source in mod_sources(&workshop_location, &workshop_ids).await {
+        let scripts = source.media.join("scripts");
+        if !scripts.is_dir() {
+            continue;
+        }
+        let mut files: Vec<PathBuf> = Vec::new();
+        if let Err(err) = collect_files(&scripts, &mut files).await {
+            index.errors.push(format!("{} -> {}", source.label(), err));
+        }
+        files.retain(|file| file.extension().is_some_and(|extension| extension == "txt"));
+        files.sort();
+
+        for file in files {
+            let name = file
+                .strip_prefix(&source.media)
+                .unwrap_or(&file)
+                .to_string_lossy()
+                .to_string();
+            match tokio::fs::read(&file).await {
+                Ok(bytes) => index.definitions.extend(parse_scripts(
+                    &decode_text(&bytes).0,
+                    &source.label(),
+                    &name,
+                )),
+                Err(err) => index.errors.push(format!("{} -> {}", file.display(), err)),
+            }
+        }
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_top_level_blocks_and_finds_collisions() {
+        let first = "module Base {\n imports { Base }\n /* item Hidden { } */\n\
+            item Axe { Weight = 3, // heavy\n DisplayName = Axe, }\n\
+            recipe Make Spear { Plank, Result:Spear, }\n\
+            vehicle Van { part Door { } }\n}";
+        let second = "module Base { item Axe { Weight = 1, } }\nmodule Extra { item Axe { } }";
+
+        let mut index = ScriptIndex {
+            definitions: parse_scripts(first, "A (1)", "scripts/a.txt"),
+            ..Default::default()
+        };
+        let ids: Vec<String> = index.definitions.iter().map(|definition| definition.id()).collect();
+        assert_eq!(ids, vec!["Base.Axe", "Base.Make Spear", "Base.Van"]);
+
+        index.definitions.extend(parse_scripts(second, "B (2)", "scripts/b.txt"));
+        let collisions = index.collisions();
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].id, "Base.Axe");
+        assert_eq!(collisions[0].sources, vec!["A (1)", "B (2)"]);
+    }
+}
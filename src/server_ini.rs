@@ -0,0 +1,322 @@
+use crate::AppMessage;
+use crate::custom_theme::{button, label_container_style, label_font, scrollable, text};
+use crate::form::{FieldKind, form_field, same_value, validate};
+use iced::widget::{column, container, row};
+use iced::{Element, Length, Renderer};
+use iced_core::Theme;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy)]
+pub struct IniKey {
+    pub key: &'static str,
+    pub category: &'static str,
+    pub kind: FieldKind,
+    pub default: &'static str,
+    pub description: &'static str,
+}
+
+const fn key(
+    key: &'static str,
+    category: &'static str,
+    kind: FieldKind,
+    default: &'static str,
+    description: &'static str,
+) -> IniKey {
+    IniKey { key, category, kind, default, description }
+}
+
+const PORT: FieldKind = FieldKind::Integer(0, 65535);
+const MAX: i64 = 2147483647;
+
+pub const SERVER_KEYS: &[IniKey] = &[
+    key("PublicName", "General", FieldKind::Text, "My PZ Server", "Name shown in the server browser"),
+    key("PublicDescription", "General", FieldKind::Text, "", "Description shown in the server browser"),
+    key("ServerWelcomeMessage", "General", FieldKind::Text, "", "Message shown in chat on join"),
+    key("Public", "General", FieldKind::Boolean, "false", "List the server in the in-game browser"),
+    key("Open", "General", FieldKind::Boolean, "true", "Allow joining without being whitelisted"),
+    key("Password", "General", FieldKind::Text, "", "Password required to join"),
+    key("PVP", "General", FieldKind::Boolean, "true", "Players can hurt each other"),
+    key("PauseEmpty", "General", FieldKind::Boolean, "true", "Pause the game when nobody is online"),
+    key("GlobalChat", "General", FieldKind::Boolean, "true", "Enable /all chat"),
+    key("SaveWorldEveryMinutes", "General", FieldKind::Integer(0, MAX), "0", "Autosave interval, 0 disables"),
+    key("NoFire", "General", FieldKind::Boolean, "false", "Disable all fire except campfires"),
+    key("AnnounceDeath", "General", FieldKind::Boolean, "false", "Announce player deaths in chat"),
+    key("DefaultPort", "Network", PORT, "16261", "Main UDP port"),
+    key("UDPPort", "Network", PORT, "16262", "Secondary UDP port"),
+    key("UPnP", "Network", FieldKind::Boolean, "true", "Try to forward ports with UPnP"),
+    key("PingLimit", "Network", FieldKind::Integer(0, MAX), "400", "Kick players above this ping, 0 disables"),
+    key("RCONPort", "Network", PORT, "27015", "Port for remote console"),
+    key("RCONPassword", "Network", FieldKind::Text, "", "Remote console password, empty disables"),
+    key("DenyLoginOnOverloadedServer", "Network", FieldKind::Boolean, "true", "Refuse logins while the server is lagging"),
+    key("MaxPlayers", "Players", FieldKind::Integer(1, 100), "32", "Maximum players online"),
+    key("MaxAccountsPerUser", "Players", FieldKind::Integer(0, MAX), "0", "Accounts per Steam user, 0 is unlimited"),
+    key("AutoCreateUserInWhiteList", "Players", FieldKind::Boolean, "false", "Whitelist players on first join"),
+    key("DropOffWhiteListAfterDeath", "Players", FieldKind::Boolean, "false", "Remove from whitelist on death"),
+    key("DisplayUserName", "Players", FieldKind::Boolean, "true", "Show names above players"),
+    key("SpawnPoint", "Players", FieldKind::Text, "0,0,0", "Forced spawn x,y,z, 0,0,0 uses spawn regions"),
+    key("SpawnItems", "Players", FieldKind::Text, "", "Items given on spawn, e.g. Base.Axe,Base.Bag"),
+    key("SafetySystem", "Players", FieldKind::Boolean, "true", "Allow players to toggle PVP safety"),
+    key("ShowSafety", "Players", FieldKind::Boolean, "true", "Show the safety skull icon"),
+    key("AllowCoop", "Players", FieldKind::Boolean, "true", "Allow splitscreen players"),
+    key("SleepAllowed", "Players", FieldKind::Boolean, "false", "Players can sleep"),
+    key("SleepNeeded", "Players", FieldKind::Boolean, "false", "Players need to sleep"),
+    key("HoursForLootRespawn", "Players", FieldKind::Integer(0, MAX), "0", "Hours before loot respawns"),
+    key("PlayerSafehouse", "Safehouses", FieldKind::Boolean, "false", "Players can claim safehouses"),
+    key("AdminSafehouse", "Safehouses", FieldKind::Boolean, "false", "Only admins can claim safehouses"),
+    key("SafehouseAllowTrepass", "Safehouses", FieldKind::Boolean, "true", "Non-members can enter"),
+    key("SafehouseAllowFire", "Safehouses", FieldKind::Boolean, "true", "Fire can damage safehouses"),
+    key("SafehouseAllowLoot", "Safehouses", FieldKind::Boolean, "true", "Non-members can take items"),
+    key("SafehouseAllowRespawn", "Safehouses", FieldKind::Boolean, "false", "Members respawn in the safehouse"),
+    key("SafehouseDaySurvivedToClaim", "Safehouses", FieldKind::Integer(0, 50), "0", "Days survived before claiming"),
+    key("SafeHouseRemovalTime", "Safehouses", FieldKind::Integer(0, 1440), "144", "Hours of inactivity before removal"),
+    key("Faction", "Safehouses", FieldKind::Boolean, "true", "Allow factions"),
+    key("SteamVAC", "Steam", FieldKind::Boolean, "true", "Enable Valve Anti-Cheat"),
+    key("SteamScoreboard", "Steam", FieldKind::Text, "true", "true, false or admin"),
+    key("VoiceEnable", "Voice", FieldKind::Boolean, "true", "Enable VOIP"),
+    key("VoiceMinDistance", "Voice", FieldKind::Double(0.0, 100000.0), "10.0", "Distance at full volume"),
+    key("VoiceMaxDistance", "Voice", FieldKind::Double(0.0, 100000.0), "100.0", "Distance voices stop"),
+    key("Voice3D", "Voice", FieldKind::Boolean, "true", "Directional audio"),
+    key("DiscordEnable", "Discord", FieldKind::Boolean, "false", "Relay chat to Discord"),
+    key("DiscordToken", "Discord", FieldKind::Text, "", "Bot token"),
+    key("DiscordChannel", "Discord", FieldKind::Text, "", "Channel name"),
+    key("DiscordChannelID", "Discord", FieldKind::Text, "", "Channel ID"),
+    key("Mods", "Mods", FieldKind::Text, "", "Mod IDs separated by ;"),
+    key("WorkshopItems", "Mods", FieldKind::Text, "", "Workshop IDs separated by ;"),
+    key("Map", "Mods", FieldKind::Text, "Muldraugh, KY", "Map folders separated by ;"),
+    key("DoLuaChecksum", "Mods", FieldKind::Boolean, "true", "Kick clients whose lua files differ"),
+];
+
+pub const INI_CATEGORIES: [&str; 8] = [
+    "General", "Network", "Players", "Safehouses", "Steam", "Voice", "Discord", "Mods",
+];
+pub const UNKNOWN_CATEGORY: &str = "Unknown";
+
+//=== Line preserving model of <server>.ini =====
+
+#[derive(Debug, Clone, Default)]
+pub struct IniDocument {
+    lines: Vec<String>,
+    line_ending: String,
+    keys: Vec<(String, usize)>,
+}
+
+impl IniDocument {
+    pub fn parse(content: &str) -> IniDocument {
+        let line_ending = match content.contains("\r\n") {
+            true => "\r\n",
+            false => "\n",
+        };
+        let mut document = IniDocument {
+            lines: content.lines().map(|line| line.to_string()).collect(),
+            line_ending: line_ending.to_string(),
+            keys: Vec::new(),
+        };
+        document.index();
+        document
+    }
+
+    fn index(&mut self) {
+        self.keys.clear();
+        for (line_number, line) in self.lines.iter().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+                continue;
+            }
+            if let Some((key, _value)) = trimmed.split_once('=') {
+                self.keys.push((key.trim().to_string(), line_number));
+            }
+        }
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        self.keys.iter().map(|(key, _)| key.clone()).collect()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.keys
+            .iter()
+            .find(|(known, _)| known == key)
+            .and_then(|(_, line)| self.lines[*line].split_once('='))
+            .map(|(_, value)| value.trim())
+    }
+
+    // Rewrites the existing line in place, or appends the key at the end
+    pub fn set(&mut self, key: &str, value: &str) {
+        match self.keys.iter().find(|(known, _)| known == key) {
+            Some((_, line)) => self.lines[*line] = format!("{}={}", key, value),
+            None => self.lines.push(format!("{}={}", key, value)),
+        }
+        self.index();
+    }
+
+    pub fn render(&self) -> String {
+        let mut output = self.lines.join(&self.line_ending);
+        output.push_str(&self.line_ending);
+        output
+    }
+}
+
+pub fn schema_key(key: &str) -> Option<&'static IniKey> {
+    SERVER_KEYS.iter().find(|known| known.key == key)
+}
+
+#[derive(Debug, Default)]
+pub struct ServerIniEditor {
+    pub path: String,
+    pub document: IniDocument,
+    pub inputs: HashMap<String, String>,
+    pub category: String,
+    pub status: String,
+}
+
+impl ServerIniEditor {
+    pub fn load(&mut self, path: String, content: Option<String>) {
+        self.document = IniDocument::parse(&content.unwrap_or_default());
+        self.inputs.clear();
+        for known in SERVER_KEYS {
+            let value = self.document.get(known.key).unwrap_or(known.default);
+            self.inputs.insert(known.key.to_string(), value.to_string());
+        }
+        for unknown in self.unknown_keys() {
+            let value = self.document.get(&unknown).unwrap_or_default().to_string();
+            self.inputs.insert(unknown, value);
+        }
+        self.path = path;
+        self.category = INI_CATEGORIES[0].to_string();
+        self.status = String::new();
+    }
+
+    pub fn unknown_keys(&self) -> Vec<String> {
+        self.document
+            .keys()
+            .into_iter()
+            .filter(|key| schema_key(key).is_none())
+            .collect()
+    }
+
+    pub fn invalid_fields(&self) -> Vec<String> {
+        SERVER_KEYS
+            .iter()
+            .filter(|known| validate(known.kind, &self.inputs[known.key]).is_err())
+            .map(|known| known.key.to_string())
+            .collect()
+    }
+
+    // Only keys whose value actually changed are touched
+    pub fn apply(&mut self) -> usize {
+        let mut changed = 0;
+        let mut keys: Vec<String> = SERVER_KEYS.iter().map(|known| known.key.to_string()).collect();
+        keys.extend(self.unknown_keys());
+        for key in keys {
+            let (kind, default) = match schema_key(&key) {
+                Some(known) => (known.kind, known.default),
+                None => (FieldKind::Text, ""),
+            };
+            let Ok(value) = validate(kind, &self.inputs[&key]) else {
+                continue;
+            };
+            if !same_value(kind, self.document.get(&key).unwrap_or(default), &value) {
+                self.document.set(&key, &value);
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    pub fn server_ini_view(&self) -> iced::widget::Container<'_, AppMessage> {
+        let unknown = self.unknown_keys();
+        let mut tabs = row![];
+        for category in INI_CATEGORIES {
+            tabs = tabs.push(
+                button(text(category))
+                    .padding(4)
+                    .on_press(AppMessage::ServerIniCategory(category.to_string())),
+            );
+        }
+        tabs = tabs.push(
+            button(text(format!("{} ({})", UNKNOWN_CATEGORY, unknown.len())))
+                .padding(4)
+                .on_press(AppMessage::ServerIniCategory(UNKNOWN_CATEGORY.to_string())),
+        );
+
+        let mut fields = column![];
+        if self.category == UNKNOWN_CATEGORY {
+            for key in unknown {
+                fields = fields.push(self.unknown_field(key));
+            }
+        } else {
+            for known in SERVER_KEYS.iter().filter(|known| known.category == self.category) {
+                let key = known.key.to_string();
+                fields = fields.push(form_field(
+                    known.key,
+                    known.kind,
+                    &self.inputs[known.key],
+                    known.default,
+                    known.description,
+                    move |value| AppMessage::ServerIniField(key.clone(), value),
+                ));
+            }
+        }
+
+        container(column![
+            row![
+                container(text(format!("Server settings: {}", self.path)).font(label_font()))
+                    .padding(8)
+                    .style(label_container_style)
+            ],
+            tabs.spacing(4).padding(5),
+            scrollable(fields.padding(5)).height(Length::Fill),
+            row![
+                button(text("Save")).on_press(AppMessage::ServerIniSave),
+                button(text("Return Home")).on_press(AppMessage::UpdateView(crate::State::InitialMain)),
+                text(&self.status)
+            ]
+            .spacing(10)
+            .padding(5)
+        ])
+    }
+
+    fn unknown_field(&self, key: String) -> Element<'_, AppMessage, Theme, Renderer> {
+        let input = self.inputs.get(&key).map(|value| value.as_str()).unwrap_or_default();
+        let label = self
+            .inputs
+            .get_key_value(&key)
+            .map(|(label, _)| label.as_str())
+            .unwrap_or_default();
+        form_field(
+            label,
+            FieldKind::Text,
+            input,
+            "",
+            "not a known server setting",
+            move |value| AppMessage::ServerIniField(key.clone(), value),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_only_touches_changed_lines() {
+        let source = "# Players can hurt each other\nPVP=true\nMaxPlayers=32\nSomeModSetting=abc\n";
+        let mut editor = ServerIniEditor::default();
+        editor.load("servertest.ini".to_string(), Some(source.to_string()));
+        editor.inputs.insert("MaxPlayers".to_string(), "16".to_string());
+
+        assert_eq!(editor.unknown_keys(), vec!["SomeModSetting"]);
+        assert_eq!(editor.apply(), 1);
+        assert_eq!(editor.document.render(), source.replace("32", "16"));
+    }
+
+    #[test]
+    fn untouched_doubles_round_trip_byte_for_byte() {
+        let source = "VoiceMinDistance=0.10\nVoiceMaxDistance=100\n";
+        let mut editor = ServerIniEditor::default();
+        editor.load("servertest.ini".to_string(), Some(source.to_string()));
+
+        assert_eq!(editor.apply(), 0);
+        assert_eq!(editor.document.render(), source);
+    }
+}
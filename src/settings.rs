@@ -0,0 +1,358 @@
+use crate::AppMessage;
+use crate::config::LIN_CONFIG_LOC;
+use crate::custom_theme::{
+    ThemeChoice, button, checkbox, label_container_style, label_font, text, text_font, text_input, text_size,
+};
+use iced::font::Family;
+use crate::explorer::PickerPurpose;
+use iced::widget::{column, container, radio, row};
+use iced::{Font, Length, Pixels, alignment::Vertical};
+use std::env::home_dir;
+use std::path::PathBuf;
+use tokio::fs;
+
+pub const SETTINGS_FILE: &str = "settings";
+const SCALE_RANGE: (u16, u16) = (50, 300);
+const SCALE_STEP: u16 = 10;
+const TEXT_SIZE_RANGE: (u16, u16) = (10, 40);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FontChoice {
+    #[default]
+    SansSerif,
+    Serif,
+    Monospace,
+}
+
+impl FontChoice {
+    pub const ALL: [FontChoice; 3] = [FontChoice::SansSerif, FontChoice::Serif, FontChoice::Monospace];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FontChoice::SansSerif => "sans_serif",
+            FontChoice::Serif => "serif",
+            FontChoice::Monospace => "monospace",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<FontChoice> {
+        FontChoice::ALL.into_iter().find(|choice| choice.name() == name)
+    }
+
+    pub fn font(&self) -> Font {
+        Font {
+            family: match self {
+                FontChoice::SansSerif => Family::SansSerif,
+                FontChoice::Serif => Family::Serif,
+                FontChoice::Monospace => Family::Monospace,
+            },
+            ..Font::DEFAULT
+        }
+    }
+}
+
+impl std::fmt::Display for FontChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            FontChoice::SansSerif => "Sans Serif",
+            FontChoice::Serif => "Serif",
+            FontChoice::Monospace => "Monospace",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ModLayout {
+    #[default]
+    List,
+    Grid,
+}
+
+impl ModLayout {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ModLayout::List => "list",
+            ModLayout::Grid => "grid",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<ModLayout> {
+        [ModLayout::List, ModLayout::Grid].into_iter().find(|layout| layout.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeSetting {
+    Scale,
+    Text,
+    Input,
+}
+
+// Sizes handed to views that set their own text size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextOptions {
+    pub buffer_size: u16,
+    pub ui_size: u16,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        TextOptions {
+            buffer_size: 18,
+            ui_size: 18,
+        }
+    }
+}
+
+// Stored as key=value lines, unknown keys are ignored so older builds can read newer files
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub theme: ThemeChoice,
+    pub font: FontChoice,
+    pub scale_percent: u16,
+    pub text_size: u16,
+    pub input_size: u16,
+    pub layout: ModLayout,
+    // Folder of the vanilla map, empty to look next to the workshop folder
+    pub vanilla_map: String,
+    // Where pinned copies go, empty for ~/Zomboid/mods
+    pub local_mods: String,
+    // Hash file contents for the change report instead of trusting size and mtime
+    pub content_hashes: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            theme: ThemeChoice::default(),
+            font: FontChoice::default(),
+            scale_percent: 100,
+            text_size: 16,
+            input_size: TextOptions::default().buffer_size,
+            layout: ModLayout::default(),
+            vanilla_map: String::new(),
+            local_mods: String::new(),
+            content_hashes: false,
+        }
+    }
+}
+
+fn parse_size(value: &str, (min, max): (u16, u16)) -> Option<u16> {
+    value.parse::<u16>().ok().map(|size| size.clamp(min, max))
+}
+
+impl Settings {
+    pub fn parse(content: &str) -> Settings {
+        let mut settings = Settings::default();
+        for line in content.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "theme" => settings.theme = ThemeChoice::from_name(value).unwrap_or(settings.theme),
+                "font" => settings.font = FontChoice::from_name(value).unwrap_or(settings.font),
+                "scale" => {
+                    settings.scale_percent = parse_size(value, SCALE_RANGE).unwrap_or(settings.scale_percent)
+                }
+                "text_size" => {
+                    settings.text_size = parse_size(value, TEXT_SIZE_RANGE).unwrap_or(settings.text_size)
+                }
+                "input_size" => {
+                    settings.input_size = parse_size(value, TEXT_SIZE_RANGE).unwrap_or(settings.input_size)
+                }
+                "layout" => settings.layout = ModLayout::from_name(value).unwrap_or(settings.layout),
+                "vanilla_map" => settings.vanilla_map = value.to_string(),
+                "local_mods" => settings.local_mods = value.to_string(),
+                "content_hashes" => settings.content_hashes = value == "true",
+                _ => {}
+            }
+        }
+        settings
+    }
+
+    pub fn render(&self) -> String {
+        format!(
+            "theme={}\nfont={}\nscale={}\ntext_size={}\ninput_size={}\nlayout={}\nvanilla_map={}\nlocal_mods={}\ncontent_hashes={}\n",
+            self.theme.name(),
+            self.font.name(),
+            self.scale_percent,
+            self.text_size,
+            self.input_size,
+            self.layout.name(),
+            self.vanilla_map,
+            self.local_mods,
+            self.content_hashes
+        )
+    }
+
+    pub fn local_mods_dir(&self) -> PathBuf {
+        match self.local_mods.trim() {
+            "" => home_dir().unwrap_or_default().join("Zomboid").join("mods"),
+            configured => PathBuf::from(configured),
+        }
+    }
+
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_percent as f64 / 100.0
+    }
+
+    pub fn text_options(&self) -> TextOptions {
+        TextOptions {
+            buffer_size: self.input_size,
+            ui_size: self.text_size,
+        }
+    }
+
+    // Startup values for iced, widgets built through custom_theme follow later changes
+    pub fn iced_settings(&self) -> iced::Settings {
+        iced::Settings {
+            default_font: self.font.font(),
+            default_text_size: Pixels(self.text_size as f32),
+            antialiasing: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn adjust(&mut self, setting: SizeSetting, steps: i32) {
+        let step = |value: u16, size: u16, (min, max): (u16, u16)| {
+            (value as i32 + steps * size as i32).clamp(min as i32, max as i32) as u16
+        };
+        match setting {
+            SizeSetting::Scale => self.scale_percent = step(self.scale_percent, SCALE_STEP, SCALE_RANGE),
+            SizeSetting::Text => self.text_size = step(self.text_size, 1, TEXT_SIZE_RANGE),
+            SizeSetting::Input => self.input_size = step(self.input_size, 1, TEXT_SIZE_RANGE),
+        }
+    }
+
+    fn size_row(&self, label: &str, setting: SizeSetting, value: String) -> iced::widget::Row<'_, AppMessage> {
+        row![
+            text(label.to_string()).width(Length::Fixed(140.0)),
+            button(text("-")).on_press(AppMessage::AdjustSetting(setting, -1)),
+            text(value).width(Length::Fixed(60.0)),
+            button(text("+")).on_press(AppMessage::AdjustSetting(setting, 1))
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center)
+    }
+
+    pub fn settings_view(&self) -> iced::widget::Container<'_, AppMessage> {
+        let mut themes = column![text("Theme").font(label_font())].spacing(8);
+        for choice in ThemeChoice::ALL {
+            themes = themes.push(radio(
+                choice.to_string(),
+                choice,
+                Some(self.theme),
+                AppMessage::ThemeSelected,
+            )
+            .font(text_font())
+            .text_size(text_size()));
+        }
+        let mut fonts = column![text("Font").font(label_font())].spacing(8);
+        for choice in FontChoice::ALL {
+            fonts = fonts.push(radio(
+                choice.to_string(),
+                choice,
+                Some(self.font),
+                AppMessage::FontSelected,
+            )
+            .font(choice.font())
+            .text_size(text_size()));
+        }
+        let sizes = column![
+            text("Sizes").font(label_font()),
+            self.size_row("UI Scale (Ctrl +/-)", SizeSetting::Scale, format!("{}%", self.scale_percent)),
+            self.size_row("Text Size", SizeSetting::Text, self.text_size.to_string()),
+            self.size_row("Input Size", SizeSetting::Input, self.input_size.to_string())
+        ]
+        .spacing(8);
+
+        container(column![
+            row![
+                container(text("Settings").font(label_font()))
+                    .padding(8)
+                    .style(label_container_style)
+            ],
+            container(column![
+                row![themes, fonts, sizes].spacing(40),
+                column![
+                    text("Vanilla Map").font(label_font()),
+                    row![
+                        text_input("e.g. .../ProjectZomboid/media/maps/Muldraugh, KY", &self.vanilla_map)
+                            .on_input(AppMessage::VanillaMapInput)
+                            .on_submit(AppMessage::SaveSettings),
+                        button(text("Browse")).on_press(AppMessage::OpenPicker(PickerPurpose::VanillaMap)),
+                        button(text("Save")).on_press(AppMessage::SaveSettings)
+                    ]
+                    .spacing(10),
+                    text("Used to find map mods overwriting vanilla cells, left empty the game install next to the workshop folder is used").size(12)
+                ]
+                .spacing(8),
+                column![
+                    text("Local Mods").font(label_font()),
+                    row![
+                        text_input("e.g. ~/Zomboid/mods", &self.local_mods)
+                            .on_input(AppMessage::LocalModsInput)
+                            .on_submit(AppMessage::SaveSettings),
+                        button(text("Browse")).on_press(AppMessage::OpenPicker(PickerPurpose::LocalMods)),
+                        button(text("Save")).on_press(AppMessage::SaveSettings)
+                    ]
+                    .spacing(10),
+                    text(format!("Pinned mods are copied to {}", self.local_mods_dir().display())).size(12)
+                ]
+                .spacing(8),
+                column![
+                    text("Change Detection").font(label_font()),
+                    checkbox("Compare file contents", self.content_hashes)
+                        .on_toggle(AppMessage::ToggleContentHashes),
+                    text("Reads every updated file on startup, off only compares sizes and modification times").size(12)
+                ]
+                .spacing(8)
+            ]
+            .spacing(20))
+                .padding(10)
+                .height(Length::Fill),
+            row![button(text("Return Home")).on_press(AppMessage::UpdateView(crate::State::InitialMain))]
+                .padding(5)
+        ])
+    }
+}
+
+// Read before the window opens so the first frame already uses the saved font and sizes
+pub fn load_settings() -> Settings {
+    match std::fs::read_to_string(LIN_CONFIG_LOC.to_owned() + SETTINGS_FILE) {
+        Ok(content) => Settings::parse(&content),
+        Err(_err) => Settings::default(),
+    }
+}
+
+pub async fn save_settings(settings: Settings) {
+    if let Err(err) = fs::write(LIN_CONFIG_LOC.to_owned() + SETTINGS_FILE, settings.render()).await {
+        println!("Error writing settings -> Err: {}", err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_round_trip_and_ignore_unknown_keys() {
+        let mut settings = Settings {
+            theme: ThemeChoice::HighContrast,
+            font: FontChoice::Monospace,
+            layout: ModLayout::Grid,
+            vanilla_map: "/games/ProjectZomboid/media/maps/Muldraugh, KY".to_string(),
+            local_mods: "/srv/zomboid/mods".to_string(),
+            content_hashes: true,
+            ..Default::default()
+        };
+        settings.adjust(SizeSetting::Scale, 3);
+        settings.adjust(SizeSetting::Text, -100);
+
+        assert_eq!(settings.scale_percent, 130);
+        assert_eq!(settings.text_size, TEXT_SIZE_RANGE.0);
+        assert_eq!(Settings::parse(&settings.render()), settings);
+        assert_eq!(Settings::parse("theme=neon\nscale=x\nfuture=1\n"), Settings::default());
+    }
+}
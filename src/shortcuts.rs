@@ -0,0 +1,95 @@
+use crate::custom_theme::{button, label_container_style, label_font, scroll_container_style, text};
+use crate::settings::SizeSetting;
+use crate::{AppMessage, ZSMM};
+use iced::keyboard::{self, Key, Modifiers, key::Named};
+use iced::widget::{column, container, row};
+use iced::{Length, Subscription, alignment::Horizontal};
+
+pub const SHORTCUTS: [(&str, &str); 15] = [
+    ("Ctrl+F", "Focus the mod search"),
+    ("Ctrl+S", "Save preset"),
+    ("Ctrl+E", "Export selections"),
+    ("Ctrl+Z", "Undo selection change"),
+    ("Ctrl+Shift+Z", "Redo selection change"),
+    ("Ctrl+Plus / Ctrl+Minus", "Scale the interface up or down"),
+    ("Ctrl+0", "Reset the interface scale"),
+    ("Up / Down", "Move through the mod list or folders"),
+    ("Space", "Toggle the selected mod"),
+    ("Enter", "Open the highlighted folder"),
+    ("Backspace", "Go up a folder in the explorer"),
+    ("Tab", "Complete the folder typed in the explorer path"),
+    ("F1 / ?", "Show or hide this help"),
+    ("Esc", "Close this help"),
+    ("", "Shortcuts are ignored while typing in a text box"),
+];
+
+pub fn subscription(_app: &ZSMM) -> Subscription<AppMessage> {
+    keyboard::on_key_press(shortcut)
+}
+
+fn shortcut(key: Key, modifiers: Modifiers) -> Option<AppMessage> {
+    match key.as_ref() {
+        Key::Character(character) if modifiers.command() => {
+            match character.to_lowercase().as_str() {
+                "z" if modifiers.shift() => Some(AppMessage::Redo),
+                "z" => Some(AppMessage::Undo),
+                "f" => Some(AppMessage::FocusSearch),
+                "s" => Some(AppMessage::SaveShortcut),
+                "e" => Some(AppMessage::ExportShortcut),
+                "+" | "=" => Some(AppMessage::AdjustSetting(SizeSetting::Scale, 1)),
+                "-" => Some(AppMessage::AdjustSetting(SizeSetting::Scale, -1)),
+                "0" => Some(AppMessage::ResetScale),
+                _ => None,
+            }
+        }
+        Key::Character("?") => Some(AppMessage::ToggleHelp),
+        Key::Named(Named::F1) => Some(AppMessage::ToggleHelp),
+        Key::Named(Named::Escape) => Some(AppMessage::CloseHelp),
+        Key::Named(Named::ArrowUp) => Some(AppMessage::Navigate(-1)),
+        Key::Named(Named::ArrowDown) => Some(AppMessage::Navigate(1)),
+        Key::Named(Named::Space) => Some(AppMessage::ToggleCurrent),
+        Key::Named(Named::Enter) => Some(AppMessage::Activate),
+        Key::Named(Named::Backspace) => Some(AppMessage::Back),
+        Key::Named(Named::Tab) => Some(AppMessage::ExplorerComplete),
+        _ => None,
+    }
+}
+
+pub fn help_view<'a>() -> iced::widget::Container<'a, AppMessage> {
+    let mut keys = column![];
+    let mut actions = column![];
+    for (key, action) in SHORTCUTS {
+        keys = keys.push(text(key).font(label_font()));
+        actions = actions.push(text(action));
+    }
+
+    container(
+        container(column![
+            container(text("Keyboard Shortcuts").font(label_font()))
+                .padding(8)
+                .style(label_container_style),
+            row![keys, actions].spacing(20).padding(10),
+            button(text("Close")).on_press(AppMessage::CloseHelp)
+        ])
+        .padding(10)
+        .style(scroll_container_style),
+    )
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .align_x(Horizontal::Center)
+    .center_y(Length::Fill)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_goes_through_the_view_guard() {
+        assert!(matches!(
+            shortcut(Key::Character("e".into()), Modifiers::COMMAND),
+            Some(AppMessage::ExportShortcut)
+        ));
+        assert!(shortcut(Key::Character("e".into()), Modifiers::empty()).is_none());
+    }
+}
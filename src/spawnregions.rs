@@ -0,0 +1,317 @@
+use crate::AppMessage;
+use crate::custom_theme::{button, checkbox, label_container_style, label_font, scrollable, text};
+use crate::localmodinfo::{collect_files, collect_map_names};
+use iced::widget::{column, container, row};
+use iced::{Element, Length, Renderer};
+use iced_core::Theme;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+pub const VANILLA_REGIONS: [&str; 4] = [
+    "Muldraugh, KY",
+    "Riverside, KY",
+    "Rosewood, KY",
+    "West Point, KY",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpawnRegion {
+    pub name: String,
+    pub file: String,
+    // `serverfile = ` entries point next to the server ini instead of into media/
+    pub server_file: bool,
+    pub enabled: bool,
+    pub added: bool,
+    // Source line of the entry, None for regions added from the selected maps
+    pub line: Option<usize>,
+    // Entries sharing a line with another one can't be commented out on their own
+    pub locked: bool,
+}
+
+impl SpawnRegion {
+    pub fn for_map(map: &str) -> SpawnRegion {
+        SpawnRegion {
+            name: map.to_string(),
+            file: format!("media/maps/{}/spawnpoints.lua", map),
+            server_file: false,
+            enabled: true,
+            added: false,
+            line: None,
+            locked: false,
+        }
+    }
+
+    fn render(&self) -> String {
+        let key = match self.server_file {
+            true => "serverfile",
+            false => "file",
+        };
+        let comment = match self.enabled {
+            true => "",
+            false => "--",
+        };
+        format!(
+            "{}\t\t{{ name = \"{}\", {} = \"{}\" }},",
+            comment, self.name, key, self.file
+        )
+    }
+}
+
+fn is_comment(line: &str) -> bool {
+    line.trim_start().starts_with("--")
+}
+
+// Adds or removes the leading `--` of an entry line, everything else stays as written
+fn toggle_comment(line: &str, enabled: bool) -> String {
+    if is_comment(line) != enabled {
+        return line.to_string();
+    }
+    let indent = &line[..line.len() - line.trim_start().len()];
+    match enabled {
+        true => format!("{}{}", indent, &line.trim_start()[2..]),
+        false => format!("--{}", line),
+    }
+}
+
+// `field = "value"` with any spacing around the `=`, not matching inside a longer key
+fn quoted_field(segment: &str, field: &str) -> Option<String> {
+    for (start, _) in segment.match_indices(field) {
+        let before = segment[..start].chars().next_back();
+        if before.is_some_and(|character| character.is_alphanumeric() || character == '_') {
+            continue;
+        }
+        let Some(rest) = segment[start + field.len()..].trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let Some(rest) = rest.trim_start().strip_prefix('"') else {
+            continue;
+        };
+        let end = rest.find('"')?;
+        return Some(rest[..end].to_string());
+    }
+    None
+}
+
+fn parse_entry(segment: &str) -> Option<(String, String, bool)> {
+    let name = quoted_field(segment, "name")?;
+    match quoted_field(segment, "serverfile") {
+        Some(file) => Some((name, file, true)),
+        None => quoted_field(segment, "file").map(|file| (name, file, false)),
+    }
+}
+
+// Keeps every line of the file, only entry lines toggled in the editor are rewritten
+#[derive(Debug, Clone, Default)]
+pub struct SpawnRegionsDocument {
+    lines: Vec<String>,
+    line_ending: String,
+    trailing_newline: bool,
+    pub regions: Vec<SpawnRegion>,
+}
+
+impl SpawnRegionsDocument {
+    pub fn parse(content: &str) -> SpawnRegionsDocument {
+        let line_ending = match content.contains("\r\n") {
+            true => "\r\n",
+            false => "\n",
+        };
+        let mut document = SpawnRegionsDocument {
+            lines: content.lines().map(|line| line.to_string()).collect(),
+            line_ending: line_ending.to_string(),
+            trailing_newline: content.ends_with('\n'),
+            regions: Vec::new(),
+        };
+
+        for (index, line) in document.lines.iter().enumerate() {
+            let entries: Vec<(String, String, bool)> = line.split('}').filter_map(parse_entry).collect();
+            let locked = entries.len() > 1;
+            for (name, file, server_file) in entries {
+                document.regions.push(SpawnRegion {
+                    name,
+                    file,
+                    server_file,
+                    enabled: !is_comment(line),
+                    added: false,
+                    line: Some(index),
+                    locked,
+                });
+            }
+        }
+        document
+    }
+
+    pub fn vanilla() -> SpawnRegionsDocument {
+        let mut content = String::from("function SpawnRegions()\n\treturn {\n");
+        for map in VANILLA_REGIONS {
+            content.push_str(&SpawnRegion::for_map(map).render());
+            content.push('\n');
+        }
+        content.push_str("\t}\nend\n");
+        SpawnRegionsDocument::parse(&content)
+    }
+
+    // New entries go after the last existing one, or before the table's closing brace
+    fn insert_at(&self) -> usize {
+        match self.regions.iter().filter_map(|region| region.line).max() {
+            Some(line) => line + 1,
+            None => self
+                .lines
+                .iter()
+                .position(|line| line.trim_start().starts_with('}'))
+                .unwrap_or(self.lines.len()),
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut lines = self.lines.clone();
+        for region in &self.regions {
+            if let Some(line) = region.line
+                && !region.locked
+            {
+                lines[line] = toggle_comment(&self.lines[line], region.enabled);
+            }
+        }
+        let added: Vec<String> = self
+            .regions
+            .iter()
+            .filter(|region| region.line.is_none())
+            .map(|region| region.render())
+            .collect();
+        let at = self.insert_at();
+        lines.splice(at..at, added);
+
+        let mut output = lines.join(&self.line_ending);
+        if self.trailing_newline {
+            output.push_str(&self.line_ending);
+        }
+        output
+    }
+}
+
+// Map folders of the given workshop items that ship a spawnpoints.lua
+pub async fn maps_with_spawnpoints(
+    workshop_location: String,
+    workshop_ids: Vec<String>,
+) -> Vec<String> {
+    let mut maps: Vec<String> = Vec::new();
+
+    for id in workshop_ids {
+        let mod_directory = format!("{}/{}/", workshop_location, id);
+        let mut map_names: Vec<String> = Vec::new();
+        let mut files: Vec<PathBuf> = Vec::new();
+        let _ = collect_map_names(Path::new(&mod_directory), &mut map_names).await;
+        let _ = collect_files(Path::new(&mod_directory), &mut files).await;
+
+        for map in map_names {
+            let ships_spawnpoints = files.iter().any(|file| {
+                file.ends_with(Path::new("maps").join(&map).join("spawnpoints.lua"))
+            });
+            if ships_spawnpoints && !maps.contains(&map) {
+                maps.push(map);
+            }
+        }
+    }
+    maps
+}
+
+pub async fn load_spawn_regions(
+    path: String,
+    workshop_location: String,
+    workshop_ids: Vec<String>,
+) -> SpawnRegionsDocument {
+    let mut document = match fs::read_to_string(&path).await {
+        Ok(content) => SpawnRegionsDocument::parse(&content),
+        Err(_err) => SpawnRegionsDocument::vanilla(),
+    };
+
+    for map in maps_with_spawnpoints(workshop_location, workshop_ids).await {
+        if !document.regions.iter().any(|region| region.name == map) {
+            let mut region = SpawnRegion::for_map(&map);
+            region.added = true;
+            document.regions.push(region);
+        }
+    }
+    document
+}
+
+#[derive(Debug, Default)]
+pub struct SpawnRegionEditor {
+    pub path: String,
+    pub document: SpawnRegionsDocument,
+    pub status: String,
+}
+
+impl SpawnRegionEditor {
+    fn region_row(&self, index: usize) -> Element<'_, AppMessage, Theme, Renderer> {
+        let region = &self.document.regions[index];
+        let note = match (region.added, region.locked) {
+            (true, _) => "new from selected maps",
+            (false, true) => "shares a line with another entry, edit the file by hand",
+            (false, false) => "",
+        };
+        row![
+            checkbox(region.name.clone(), region.enabled)
+                .on_toggle_maybe(
+                    (!region.locked).then_some(move |bool| AppMessage::SpawnRegionToggled(index, bool))
+                )
+                .width(Length::Fixed(260.0)),
+            text(&region.file).width(Length::Fill),
+            text(note)
+        ]
+        .spacing(10)
+        .padding(2)
+        .into()
+    }
+
+    pub fn spawn_regions_view(&self) -> iced::widget::Container<'_, AppMessage> {
+        let mut regions = column![];
+        for index in 0..self.document.regions.len() {
+            regions = regions.push(self.region_row(index));
+        }
+
+        container(column![
+            row![
+                container(text(format!("Spawn regions: {}", self.path)).font(label_font()))
+                    .padding(8)
+                    .style(label_container_style)
+            ],
+            scrollable(regions.padding(5)).height(Length::Fill),
+            row![
+                button(text("Save")).on_press(AppMessage::SpawnRegionsSave),
+                button(text("Return Home")).on_press(AppMessage::UpdateView(crate::State::InitialMain)),
+                text(&self.status)
+            ]
+            .spacing(10)
+            .padding(5)
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_comments_and_compact_entries_when_toggling() {
+        let source = "-- custom spawns\nfunction SpawnRegions()\n\treturn {\n\t\t{ name = \"Muldraugh, KY\", file = \"media/maps/Muldraugh, KY/spawnpoints.lua\" },\n--\t\t{ name = \"Twdprops\", serverfile = \"servertest_spawnpoints.lua\" },\n\t\t{name=\"A\",file=\"a.lua\"},{name=\"B\",file=\"b.lua\"},\n\t}\n\tif isServer() then print(\"x\") end\nend\n";
+        let mut document = SpawnRegionsDocument::parse(source);
+
+        assert_eq!(document.regions.len(), 4);
+        assert!(document.regions[0].enabled);
+        assert!(!document.regions[1].enabled && document.regions[1].server_file);
+        assert!(document.regions[2].locked && document.regions[3].locked);
+        assert_eq!(document.regions[2].file, "a.lua");
+        assert_eq!(document.render(), source);
+
+        document.regions[0].enabled = false;
+        document.regions[1].enabled = true;
+        let mut added = SpawnRegion::for_map("Bedford Falls");
+        added.added = true;
+        document.regions.push(added);
+        let rendered = document.render();
+        assert!(rendered.contains("--\t\t{ name = \"Muldraugh, KY\""));
+        assert!(rendered.contains("\n\t\t{ name = \"Twdprops\""));
+        assert!(rendered.contains("},\n\t\t{ name = \"Bedford Falls\", file = \"media/maps/Bedford Falls/spawnpoints.lua\" },\n\t}"));
+        assert!(rendered.starts_with("-- custom spawns\n") && rendered.contains("if isServer()"));
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt;
+
+// Windows-1252 characters for 0x80..=0x9F, the five unassigned bytes keep their Latin-1 meaning
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž', '\u{8F}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}', 'ž', 'Ÿ',
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextEncoding {
+    #[default]
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+    Windows1252,
+}
+
+impl TextEncoding {
+    // Plain UTF-8 is what the game writes, anything else is worth a look
+    pub fn is_unusual(&self) -> bool {
+        *self != TextEncoding::Utf8
+    }
+}
+
+impl fmt::Display for TextEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf8Bom => "UTF-8 with BOM",
+            TextEncoding::Utf16Le => "UTF-16 LE",
+            TextEncoding::Utf16Be => "UTF-16 BE",
+            TextEncoding::Latin1 => "Latin-1",
+            TextEncoding::Windows1252 => "Windows-1252",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]))
+        .collect();
+    char::decode_utf16(units)
+        .map(|unit| unit.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+// UTF-16 is only recognised by its BOM, everything that isn't valid UTF-8 is
+// read as Windows-1252 which is a superset of the printable Latin-1 range
+pub fn decode_text(bytes: &[u8]) -> (String, TextEncoding) {
+    let (text, encoding) = if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        (String::from_utf8_lossy(rest).to_string(), TextEncoding::Utf8Bom)
+    } else if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        (decode_utf16(rest, u16::from_le_bytes), TextEncoding::Utf16Le)
+    } else if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        (decode_utf16(rest, u16::from_be_bytes), TextEncoding::Utf16Be)
+    } else if let Ok(text) = std::str::from_utf8(bytes) {
+        (text.to_string(), TextEncoding::Utf8)
+    } else {
+        let encoding = match bytes.iter().any(|byte| (0x80..=0x9F).contains(byte)) {
+            true => TextEncoding::Windows1252,
+            false => TextEncoding::Latin1,
+        };
+        let text = bytes
+            .iter()
+            .map(|&byte| match byte {
+                0x80..=0x9F => WINDOWS_1252_HIGH[(byte - 0x80) as usize],
+                _ => byte as char,
+            })
+            .collect();
+        (text, encoding)
+    };
+    (text.replace("\r\n", "\n"), encoding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_boms_and_legacy_encodings() {
+        assert_eq!(decode_text(b"id=A\r\nname=B\r\n"), ("id=A\nname=B\n".to_string(), TextEncoding::Utf8));
+        assert_eq!(decode_text(b"\xEF\xBB\xBFid=A"), ("id=A".to_string(), TextEncoding::Utf8Bom));
+        assert_eq!(decode_text(b"\xFF\xFEi\0d\0=\0A\0"), ("id=A".to_string(), TextEncoding::Utf16Le));
+        assert_eq!(decode_text(b"\xFE\xFF\0i\0d\0=\0A"), ("id=A".to_string(), TextEncoding::Utf16Be));
+        assert_eq!(decode_text(b"name=Caf\xE9"), ("name=Café".to_string(), TextEncoding::Latin1));
+        assert_eq!(
+            decode_text(b"name=\x93Quoted\x94"),
+            ("name=“Quoted”".to_string(), TextEncoding::Windows1252)
+        );
+    }
+}
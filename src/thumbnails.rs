@@ -0,0 +1,175 @@
+use crate::config::LIN_CONFIG_LOC;
+use crate::localmodinfo::{FNV_OFFSET, fnv1a};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+pub const THUMBNAIL_DIR: &str = "thumbnails";
+pub const THUMBNAIL_SIZE: u32 = 48;
+const THUMBNAIL_INDEX: &str = "index";
+
+// Keyed on the poster's path, size and modification time so an updated
+// workshop item gets a fresh thumbnail instead of the stale one. FNV-1a keeps
+// the name the same across builds, std's hasher may change between toolchains
+pub fn thumbnail_path(cache_dir: &Path, source: &Path) -> Option<PathBuf> {
+    let metadata = fs::metadata(source).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    let hash = fnv1a(FNV_OFFSET, source.as_os_str().as_encoded_bytes());
+    let hash = fnv1a(hash, &metadata.len().to_le_bytes());
+    let hash = fnv1a(hash, &modified.to_le_bytes());
+    Some(cache_dir.join(format!("{:016x}.png", hash)))
+}
+
+// <thumbnail file>\t<poster path> for the thumbnails of every workshop root scanned so far
+fn read_index(cache_dir: &Path) -> BTreeMap<String, PathBuf> {
+    fs::read_to_string(cache_dir.join(THUMBNAIL_INDEX))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(file, poster)| (file.to_string(), PathBuf::from(poster)))
+        .collect()
+}
+
+fn write_index(cache_dir: &Path, index: &BTreeMap<String, PathBuf>) -> Result<(), String> {
+    let content: String = index
+        .iter()
+        .map(|(file, poster)| format!("{}\t{}\n", file, poster.display()))
+        .collect();
+    fs::write(cache_dir.join(THUMBNAIL_INDEX), content)
+        .map_err(|err| format!("Error writing the thumbnail index -> Err: {}", err))
+}
+
+// A thumbnail goes once its poster is gone or was replaced, so switching roots or
+// profiles keeps the other root's thumbnails. Pngs missing from the index predate it
+fn prune_thumbnails(cache_dir: &Path, index: &mut BTreeMap<String, PathBuf>) -> Vec<String> {
+    let mut stale: Vec<PathBuf> = Vec::new();
+    index.retain(|file, poster| {
+        let current = thumbnail_path(cache_dir, poster);
+        let keep = current.is_some_and(|path| path.file_name().is_some_and(|name| name == file.as_str()));
+        if !keep {
+            stale.push(cache_dir.join(file));
+        }
+        keep
+    });
+    if let Ok(entries) = fs::read_dir(cache_dir) {
+        stale.extend(entries.flatten().map(|entry| entry.path()).filter(|path| {
+            path.extension().is_some_and(|extension| extension == "png")
+                && path
+                    .file_name()
+                    .is_some_and(|name| !index.contains_key(name.to_string_lossy().as_ref()))
+        }));
+    }
+
+    let mut errors: Vec<String> = Vec::new();
+    for path in stale {
+        if let Err(err) = fs::remove_file(&path)
+            && err.kind() != std::io::ErrorKind::NotFound
+        {
+            errors.push(format!("Error removing {} -> Err: {}", path.display(), err));
+        }
+    }
+    errors
+}
+
+fn cached_thumbnail(cache_dir: &Path, source: &Path) -> Result<PathBuf, String> {
+    let target = thumbnail_path(cache_dir, source)
+        .ok_or_else(|| format!("Can't read {}", source.display()))?;
+    if target.is_file() {
+        return Ok(target);
+    }
+    let poster = image::open(source).map_err(|err| format!("{} -> {}", source.display(), err))?;
+    poster
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .save(&target)
+        .map_err(|err| format!("{} -> {}", target.display(), err))?;
+    Ok(target)
+}
+
+fn thumbnail_status(errors: &[String]) -> String {
+    match errors {
+        [] => String::new(),
+        [error] => format!("Thumbnails: {}", error),
+        [error, ..] => format!("Thumbnails: {} (and {} more)", error, errors.len() - 1),
+    }
+}
+
+// Decoding runs on the blocking pool, mods without a usable poster are left out
+// and keep the blank space next to their checkbox. Problems come back as a status line
+pub async fn build_thumbnails(posters: HashMap<String, String>) -> (HashMap<String, String>, String) {
+    let cache_dir = PathBuf::from(LIN_CONFIG_LOC).join(THUMBNAIL_DIR);
+
+    let result = tokio::task::spawn_blocking(move || {
+        let mut thumbnails: HashMap<String, String> = HashMap::new();
+        if let Err(err) = fs::create_dir_all(&cache_dir) {
+            let status = format!("Error creating {} -> Err: {}", cache_dir.display(), err);
+            return (thumbnails, thumbnail_status(&[status]));
+        }
+        let mut index = read_index(&cache_dir);
+        let mut errors: Vec<String> = Vec::new();
+        for (name, poster) in posters {
+            if poster.is_empty() {
+                continue;
+            }
+            match cached_thumbnail(&cache_dir, Path::new(&poster)) {
+                Ok(path) => {
+                    if let Some(file) = path.file_name() {
+                        index.insert(file.to_string_lossy().to_string(), PathBuf::from(&poster));
+                    }
+                    thumbnails.insert(name, path.to_string_lossy().to_string());
+                }
+                Err(err) => errors.push(format!("Skipped {} -> {}", name, err)),
+            }
+        }
+        errors.extend(prune_thumbnails(&cache_dir, &mut index));
+        if let Err(err) = write_index(&cache_dir, &index) {
+            errors.push(err);
+        }
+        (thumbnails, thumbnail_status(&errors))
+    })
+    .await;
+    result.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downsizes_and_reuses_cached_thumbnail() {
+        let dir = std::env::temp_dir().join(format!("zsmm-thumbnails-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let cache = dir.join(THUMBNAIL_DIR);
+        fs::create_dir_all(&cache).unwrap();
+        let poster = dir.join("poster.png");
+        image::RgbImage::new(256, 128).save(&poster).unwrap();
+
+        let first = cached_thumbnail(&cache, &poster).unwrap();
+        let thumbnail = image::open(&first).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (THUMBNAIL_SIZE, THUMBNAIL_SIZE / 2));
+        assert_eq!(cached_thumbnail(&cache, &poster).unwrap(), first);
+
+        // Another root's poster stays cached, a thumbnail whose poster is gone doesn't
+        let other_poster = dir.join("other.png");
+        image::RgbImage::new(64, 64).save(&other_poster).unwrap();
+        let other = cached_thumbnail(&cache, &other_poster).unwrap();
+        let file_name = |path: &PathBuf| path.file_name().unwrap().to_string_lossy().to_string();
+        let mut index = BTreeMap::from([
+            (file_name(&first), poster.clone()),
+            (file_name(&other), other_poster.clone()),
+        ]);
+        fs::remove_file(&other_poster).unwrap();
+
+        assert!(prune_thumbnails(&cache, &mut index).is_empty());
+        assert!(first.is_file() && !other.exists());
+        assert_eq!(index.len(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
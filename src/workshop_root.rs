@@ -0,0 +1,153 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Project Zomboid's Steam app id, the name of every workshop content folder
+pub const ZOMBOID_APP_ID: &str = "108600";
+const SEARCH_DEPTH: usize = 3;
+const SEARCH_LIMIT: usize = 500;
+
+// Where Steam's workshop content folder usually sits relative to a home folder or Steam library
+const KNOWN_LOCATIONS: [&str; 6] = [
+    ".local/share/Steam/steamapps/workshop/content",
+    ".steam/steam/steamapps/workshop/content",
+    ".var/app/com.valvesoftware.Steam/.local/share/Steam/steamapps/workshop/content",
+    "steamapps/workshop/content",
+    "workshop/content",
+    "content",
+];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkshopStatus {
+    pub items: usize,
+    pub suggestion: Option<String>,
+}
+
+impl WorkshopStatus {
+    pub fn check(path: &Path) -> WorkshopStatus {
+        let items = workshop_item_count(path);
+        WorkshopStatus {
+            items,
+            suggestion: match items {
+                0 => suggest_workshop_root(path).map(|root| root.to_string_lossy().to_string()),
+                _ => None,
+            },
+        }
+    }
+
+    // The suggestion search can walk hundreds of folders, slow drives would freeze the window
+    pub async fn check_in_background(path: PathBuf) -> (PathBuf, WorkshopStatus) {
+        let checked = path.clone();
+        let status = tokio::task::spawn_blocking(move || WorkshopStatus::check(&checked))
+            .await
+            .unwrap_or_default();
+        (path, status)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.items > 0
+    }
+
+    pub fn summary(&self) -> String {
+        match (self.items, &self.suggestion) {
+            (0, Some(suggestion)) => {
+                format!("Not a workshop folder, try {}", suggestion)
+            }
+            (0, None) => String::from("Not a workshop folder, no mods/*/mod.info found"),
+            (items, _) => format!("Workshop folder with {} items", items),
+        }
+    }
+}
+
+fn has_mod_info(item: &Path) -> bool {
+    let Ok(mods) = fs::read_dir(item.join("mods")) else {
+        return false;
+    };
+    mods.flatten()
+        .any(|entry| entry.path().join("mod.info").is_file())
+}
+
+// Workshop items are numeric folders holding mods/<name>/mod.info
+pub fn workshop_item_count(path: &Path) -> usize {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            !name.is_empty() && name.chars().all(|character| character.is_ascii_digit())
+        })
+        .filter(|entry| has_mod_info(&entry.path()))
+        .count()
+}
+
+// Ancestors are checked first, then the usual Steam layouts below the folder,
+// then a shallow breadth first search of visible subfolders
+pub fn suggest_workshop_root(path: &Path) -> Option<PathBuf> {
+    if let Some(ancestor) = path
+        .ancestors()
+        .skip(1)
+        .find(|ancestor| workshop_item_count(ancestor) > 0)
+    {
+        return Some(ancestor.to_path_buf());
+    }
+    if let Some(known) = KNOWN_LOCATIONS
+        .iter()
+        .map(|location| path.join(location).join(ZOMBOID_APP_ID))
+        .find(|candidate| workshop_item_count(candidate) > 0)
+    {
+        return Some(known);
+    }
+
+    let mut queue: Vec<(PathBuf, usize)> = vec![(path.to_path_buf(), 0)];
+    let mut visited = 0;
+    while !queue.is_empty() && visited < SEARCH_LIMIT {
+        let (folder, depth) = queue.remove(0);
+        visited += 1;
+        if depth > 0 && workshop_item_count(&folder) > 0 {
+            return Some(folder);
+        }
+        if depth == SEARCH_DEPTH {
+            continue;
+        }
+        let Ok(entries) = fs::read_dir(&folder) else {
+            continue;
+        };
+        let mut children: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|child| child.is_dir())
+            .filter(|child| {
+                child
+                    .file_name()
+                    .is_some_and(|name| !name.to_string_lossy().starts_with('.'))
+            })
+            .collect();
+        children.sort();
+        queue.extend(children.into_iter().map(|child| (child, depth + 1)));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_items_and_suggests_ancestor_and_descendant() {
+        let base = std::env::temp_dir().join(format!("zsmm-workshop-{}", std::process::id()));
+        let root = base.join("steamapps/workshop/content/108600");
+        fs::create_dir_all(root.join("2169435993/mods/Hydrocraft")).unwrap();
+        fs::write(root.join("2169435993/mods/Hydrocraft/mod.info"), "id=Hydrocraft").unwrap();
+        fs::create_dir_all(root.join("notanitem/mods/Other")).unwrap();
+        fs::create_dir_all(root.join("2169435993/mods/Hydrocraft/media")).unwrap();
+
+        assert_eq!(workshop_item_count(&root), 1);
+        assert_eq!(workshop_item_count(&base), 0);
+        assert_eq!(suggest_workshop_root(&base), Some(root.clone()));
+        assert_eq!(suggest_workshop_root(&root.join("2169435993/mods")), Some(root.clone()));
+        assert!(WorkshopStatus::check(&root).is_valid());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+}